@@ -0,0 +1,81 @@
+//! Compares the reference scorer with the allocation-free one.
+//! Run with `cargo bench --bench score`.
+//!
+//! The crate has no library target, so the modules needed by scoring are included by path.
+#![allow(dead_code, unused_imports)]
+#![allow(clippy::unwrap_or_default)]
+#![allow(clippy::redundant_pattern_matching)]
+#![allow(clippy::useless_format)]
+
+#[path = "../src/benchmark.rs"]
+mod benchmark;
+#[path = "../src/config.rs"]
+mod config;
+// same modules as src/db.rs, path of db.rs would not apply to them
+#[path = "../src/db"]
+mod db {
+    pub mod benchmark;
+    pub mod connection;
+    pub mod export;
+    pub mod import;
+    pub mod model;
+    pub mod ops;
+    pub mod ownership;
+    pub mod retention;
+    pub mod store;
+}
+#[path = "../src/error/mod.rs"]
+mod error;
+#[path = "../src/fancy/mod.rs"]
+mod fancy;
+#[path = "../src/hash.rs"]
+mod hash;
+#[path = "../src/keystore.rs"]
+mod keystore;
+#[path = "../src/result_queue.rs"]
+mod result_queue;
+#[path = "../src/runner.rs"]
+mod runner;
+#[path = "../src/types.rs"]
+mod types;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fancy::{score_fancy_reference, score_fast};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::hint::black_box;
+use web3::types::Address;
+
+fn bench_score(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(1);
+    let addresses = (0..100)
+        .map(|_| Address::from(rng.random::<[u8; 20]>()))
+        .collect::<Vec<_>>();
+    // both scorers have to agree, otherwise the comparison is meaningless
+    for address in &addresses {
+        assert_eq!(
+            score_fancy_reference(*address).total_score,
+            score_fast(address).total_score()
+        );
+    }
+
+    let mut group = c.benchmark_group("score");
+    group.bench_function("score_fancy_reference", |b| {
+        b.iter(|| {
+            for address in &addresses {
+                black_box(score_fancy_reference(black_box(*address)));
+            }
+        })
+    });
+    group.bench_function("score_fast", |b| {
+        b.iter(|| {
+            for address in &addresses {
+                black_box(score_fast(black_box(address)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_score);
+criterion_main!(benches);
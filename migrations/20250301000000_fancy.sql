@@ -0,0 +1,21 @@
+CREATE TABLE fancy
+(
+    address         TEXT     NOT NULL PRIMARY KEY,
+    salt            TEXT     NOT NULL,
+    factory         TEXT     NULL,
+    init_code_hash  TEXT     NULL,
+    public_key_base TEXT     NULL,
+    created         DATETIME NOT NULL,
+    score           REAL     NOT NULL,
+    owner           TEXT     NULL,
+    price           INTEGER  NOT NULL,
+    category        TEXT     NOT NULL,
+    job             TEXT     NULL,
+    create3_scheme  TEXT     NULL,
+    caller          TEXT     NULL,
+    chain_id        INTEGER  NULL
+);
+
+CREATE INDEX idx_fancy_category ON fancy (category);
+CREATE INDEX idx_fancy_score ON fancy (score);
+CREATE INDEX idx_fancy_created ON fancy (created);
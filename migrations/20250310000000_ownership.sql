@@ -0,0 +1,17 @@
+ALTER TABLE fancy ADD COLUMN reserved_by TEXT NULL;
+ALTER TABLE fancy ADD COLUMN reserved_until DATETIME NULL;
+
+CREATE TABLE fancy_ownership_event
+(
+    id              INTEGER  NOT NULL PRIMARY KEY AUTOINCREMENT,
+    address         TEXT     NOT NULL,
+    action          TEXT     NOT NULL,
+    actor           TEXT     NOT NULL,
+    previous_owner  TEXT     NULL,
+    owner           TEXT     NULL,
+    reserved_by     TEXT     NULL,
+    reserved_until  DATETIME NULL,
+    created         DATETIME NOT NULL
+);
+
+CREATE INDEX idx_fancy_ownership_event_address ON fancy_ownership_event (address);
@@ -0,0 +1,20 @@
+CREATE TABLE fancy_archive
+(
+    address         TEXT     NOT NULL PRIMARY KEY,
+    salt            TEXT     NOT NULL,
+    factory         TEXT     NULL,
+    init_code_hash  TEXT     NULL,
+    public_key_base TEXT     NULL,
+    created         DATETIME NOT NULL,
+    score           REAL     NOT NULL,
+    owner           TEXT     NULL,
+    price           INTEGER  NOT NULL,
+    category        TEXT     NOT NULL,
+    job             TEXT     NULL,
+    create3_scheme  TEXT     NULL,
+    caller          TEXT     NULL,
+    chain_id        INTEGER  NULL,
+    reserved_by     TEXT     NULL,
+    reserved_until  DATETIME NULL,
+    archived        DATETIME NOT NULL
+);
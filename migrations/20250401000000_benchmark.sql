@@ -0,0 +1,18 @@
+CREATE TABLE benchmark_report
+(
+    id            INTEGER  NOT NULL PRIMARY KEY AUTOINCREMENT,
+    runner_no     INTEGER  NOT NULL,
+    device_name   TEXT     NULL,
+    app_version   TEXT     NOT NULL,
+    tool_version  TEXT     NULL,
+    target_kind   TEXT     NOT NULL,
+    duration_secs REAL     NOT NULL,
+    sample_count  INTEGER  NOT NULL,
+    mean_speed    REAL     NOT NULL,
+    stddev_speed  REAL     NOT NULL,
+    warmup_secs   REAL     NULL,
+    speed_samples TEXT     NOT NULL,
+    created       DATETIME NOT NULL
+);
+
+CREATE INDEX idx_benchmark_report_device ON benchmark_report (device_name, target_kind);
@@ -1,4 +1,12 @@
+mod addresses;
+mod benchmarks;
+mod blockies;
+mod estimate;
+mod gallery;
 mod golem;
+mod ownership;
+mod retention;
 mod runners;
 pub mod scope;
+mod score;
 pub mod utils;
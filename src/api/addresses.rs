@@ -0,0 +1,182 @@
+use crate::db::export::{export_header, export_next_batch, parse_export_columns, ExportFormat};
+use crate::db::import::{parse_import_input, store_import, verify_import, ImportFormat};
+use crate::db::model::{FancyCursor, FancyQuery, FancySortBy};
+use crate::db::ops::{count_fancy_objs, query_fancy_objs};
+use crate::runner::WorkTarget;
+use crate::types::DbAddress;
+use crate::ServerData;
+use actix_web::error::ErrorBadRequest;
+use actix_web::http::header;
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use bytes::Bytes;
+use futures_util::StreamExt;
+use serde_json::json;
+use std::str::FromStr;
+
+use super::utils::{
+    extract_url_bool_param, extract_url_date_param, extract_url_float_param, extract_url_int_param,
+    extract_url_param,
+};
+
+const DEFAULT_ADDRESSES_LIMIT: i64 = 100;
+const MAX_ADDRESSES_LIMIT: i64 = 1000;
+
+fn extract_url_address_param(
+    request: &HttpRequest,
+    param: &str,
+) -> Result<Option<DbAddress>, actix_web::Error> {
+    extract_url_param(request, param)?
+        .map(|address| {
+            DbAddress::from_str(&address)
+                .map_err(|err| ErrorBadRequest(format!("Invalid {param} {address}: {err}")))
+        })
+        .transpose()
+}
+
+/// Filters shared by every endpoint listing stored addresses
+pub fn extract_fancy_query(request: &HttpRequest) -> Result<FancyQuery, actix_web::Error> {
+    let address_regex = extract_url_param(request, "regex")?;
+    if let Some(address_regex) = &address_regex {
+        regex::Regex::new(address_regex)
+            .map_err(|err| ErrorBadRequest(format!("Invalid regex: {err}")))?;
+    }
+    let sort_by = extract_url_param(request, "sort")?
+        .map(|sort| FancySortBy::from_str(&sort).map_err(ErrorBadRequest))
+        .transpose()?
+        .unwrap_or_default();
+    let ascending = match extract_url_param(request, "order")?.as_deref() {
+        None | Some("desc") => false,
+        Some("asc") => true,
+        Some(order) => {
+            return Err(ErrorBadRequest(format!(
+                "Order has to be asc or desc, got {order}"
+            )))
+        }
+    };
+    Ok(FancyQuery {
+        category: extract_url_param(request, "category")?,
+        min_score: extract_url_float_param(request, "minScore")?,
+        max_score: extract_url_float_param(request, "maxScore")?,
+        factory: extract_url_address_param(request, "factory")?,
+        public_key_base: extract_url_param(request, "publicKeyBase")?,
+        owner: extract_url_param(request, "owner")?,
+        job: extract_url_param(request, "job")?,
+        created_from: extract_url_date_param(request, "createdFrom")?.map(|d| d.naive_utc()),
+        created_to: extract_url_date_param(request, "createdTo")?.map(|d| d.naive_utc()),
+        address_regex,
+        sort_by,
+        ascending,
+        cursor: extract_url_param(request, "cursor")?
+            .map(|cursor| FancyCursor::parse(&cursor, sort_by).map_err(ErrorBadRequest))
+            .transpose()?,
+    })
+}
+
+pub async fn list_addresses(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let query = extract_fancy_query(&request)?;
+    if extract_url_bool_param(&request, "count")?.unwrap_or(false) {
+        let count = count_fancy_objs(&data.db, &query)
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        return Ok(HttpResponse::Ok().json(json!({ "count": count })));
+    }
+    let limit = extract_url_int_param(&request, "limit")?.unwrap_or(DEFAULT_ADDRESSES_LIMIT);
+    if !(1..=MAX_ADDRESSES_LIMIT).contains(&limit) {
+        return Err(ErrorBadRequest(format!(
+            "Limit has to be between 1 and {MAX_ADDRESSES_LIMIT}"
+        )));
+    }
+    let items = query_fancy_objs(&data.db, &query, limit)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    // full page means there can be more, next page starts after the last record
+    let next_cursor = (items.len() as i64 == limit)
+        .then(|| {
+            items
+                .last()
+                .map(|item| FancyCursor::after(item, query.sort_by).to_string())
+        })
+        .flatten();
+    Ok(HttpResponse::Ok().json(json!({
+        "items": items,
+        "nextCursor": next_cursor,
+    })))
+}
+
+/// Streams export page by page, so large exports do not load all rows into memory
+pub async fn export_addresses(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let query = extract_fancy_query(&request)?;
+    let format = extract_url_param(&request, "format")?
+        .map(|format| ExportFormat::from_str(&format).map_err(ErrorBadRequest))
+        .transpose()?
+        .unwrap_or_default();
+    let columns =
+        parse_export_columns(&extract_url_param(&request, "columns")?.unwrap_or_default())
+            .map_err(|err| ErrorBadRequest(err.to_string()))?;
+    let header_chunk = Bytes::from(export_header(format, &columns));
+    let conn = data.db.clone();
+    let batches = futures_util::stream::unfold(
+        (conn, query, columns, false),
+        move |(conn, mut query, columns, finished)| async move {
+            if finished {
+                return None;
+            }
+            match export_next_batch(&conn, &mut query, format, &columns).await {
+                Ok(Some(batch)) => Some((Ok(Bytes::from(batch)), (conn, query, columns, false))),
+                Ok(None) => None,
+                Err(err) => {
+                    log::error!("Export failed: {err}");
+                    Some((
+                        Err(actix_web::error::ErrorInternalServerError(err)),
+                        (conn, query, columns, true),
+                    ))
+                }
+            }
+        },
+    );
+    let body = futures_util::stream::once(async move { Ok(header_chunk) }).chain(batches);
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"addresses.{}\"", format.extension()),
+        ))
+        .streaming(body))
+}
+
+/// Body is jsonl, csv or raw salts, target is WorkTarget as json, required for raw salts
+pub async fn import_addresses(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+    body: web::Bytes,
+) -> Result<HttpResponse, actix_web::Error> {
+    let format = extract_url_param(&request, "format")?
+        .map(|format| ImportFormat::from_str(&format).map_err(ErrorBadRequest))
+        .transpose()?;
+    let target = extract_url_param(&request, "target")?
+        .map(|target| {
+            serde_json::from_str::<WorkTarget>(&target)
+                .map_err(|err| ErrorBadRequest(format!("Invalid target: {err}")))
+        })
+        .transpose()?;
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let (verified, mut report) = web::block(move || {
+        let records = parse_import_input(&body, format)?;
+        Ok::<_, crate::error::AddressologyError>(verify_import(&records, target.as_ref(), threads))
+    })
+    .await?
+    .map_err(|err| ErrorBadRequest(err.to_string()))?;
+    store_import(&data.db, &verified, &mut report)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(report))
+}
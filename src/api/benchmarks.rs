@@ -0,0 +1,76 @@
+use crate::api::utils::{extract_url_int_param, extract_url_param};
+use crate::benchmark::{run_benchmarks, BenchmarkRequest};
+use crate::db::benchmark::{
+    compare_benchmark_report, get_benchmark_report, list_benchmark_reports,
+};
+use crate::ServerData;
+use actix_web::error::ErrorInternalServerError;
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use serde_json::json;
+
+/// Stored reports newest first, each compared with previous run on the same device
+pub async fn list_benchmarks(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let device = extract_url_param(&request, "device")?;
+    let limit = extract_url_int_param(&request, "limit")?.unwrap_or(100);
+    let reports = list_benchmark_reports(&data.db, device.as_deref(), limit)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let mut compared = Vec::with_capacity(reports.len());
+    for report in reports {
+        compared.push(
+            compare_benchmark_report(&data.db, report)
+                .await
+                .map_err(ErrorInternalServerError)?,
+        );
+    }
+    Ok(HttpResponse::Ok().json(compared))
+}
+
+pub async fn benchmark_report(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let id: i64 = match request.match_info().query("id").parse() {
+        Ok(id) => id,
+        Err(_) => return Ok(HttpResponse::BadRequest().body("Invalid report id")),
+    };
+    let Some(report) = get_benchmark_report(&data.db, id)
+        .await
+        .map_err(ErrorInternalServerError)?
+    else {
+        return Ok(HttpResponse::NotFound().body(format!("Benchmark report {id} not found")));
+    };
+    let compared = compare_benchmark_report(&data.db, report)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(compared))
+}
+
+/// Benchmark runs in background, reports show up in benchmark list when finished
+pub async fn run_benchmark(
+    data: Data<Box<ServerData>>,
+    body: web::Json<BenchmarkRequest>,
+) -> HttpResponse {
+    let request = body.into_inner();
+    let selected = match request
+        .validate()
+        .and_then(|_| request.runner_numbers(data.runners.len()))
+    {
+        Ok(selected) => selected,
+        Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
+    };
+    let conn = data.db.clone();
+    let runners = data.runners.clone();
+    tokio::spawn(async move {
+        if let Err(err) = run_benchmarks(&conn, &runners, &request).await {
+            log::error!("Benchmark failed: {err}");
+        }
+    });
+    HttpResponse::Accepted().json(json!({
+        "runners": selected,
+    }))
+}
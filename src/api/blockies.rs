@@ -0,0 +1,100 @@
+use crate::config::get_blockies_cache_max_age;
+use crate::types::DbAddress;
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse};
+use eth_blockies::{BlockiesGenerator, EthBlockies};
+use std::fmt::Write;
+
+use super::utils::extract_url_int_param;
+
+const DEFAULT_SIZE: i64 = 64;
+const MAX_SIZE: i64 = 1024;
+
+/// Blockies are seeded with lowercase address, the same way wallets render them
+fn blockies_seed(address: &DbAddress) -> String {
+    address.to_string()
+}
+
+pub fn blockies_svg(address: &DbAddress, size: usize) -> String {
+    let data = EthBlockies::data(blockies_seed(address).as_str());
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 8 8" shape-rendering="crispEdges">"#
+    );
+    for (y, row) in data.iter().enumerate() {
+        for (x, (r, g, b)) in row.iter().enumerate() {
+            let _ = write!(
+                svg,
+                r##"<rect x="{x}" y="{y}" width="1" height="1" fill="#{r:02x}{g:02x}{b:02x}"/>"##
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+pub fn blockies_png(address: &DbAddress, size: usize) -> Vec<u8> {
+    EthBlockies::png_data(blockies_seed(address).as_str(), (size, size))
+}
+
+fn parse_blockies_request(req: &HttpRequest) -> Result<(DbAddress, usize), String> {
+    let address = req.match_info().query("address");
+    let address =
+        DbAddress::from_str(address).map_err(|err| format!("Invalid address {address}: {err}"))?;
+    let size = extract_url_int_param(req, "size")
+        .map_err(|err| err.to_string())?
+        .unwrap_or(DEFAULT_SIZE);
+    if !(8..=MAX_SIZE).contains(&size) {
+        return Err(format!(
+            "Size has to be between 8 and {MAX_SIZE}, got {size}"
+        ));
+    }
+    Ok((address, size as usize))
+}
+
+fn cache_control() -> (header::HeaderName, String) {
+    (
+        header::CACHE_CONTROL,
+        format!(
+            "public, max-age={}, immutable",
+            get_blockies_cache_max_age()
+        ),
+    )
+}
+
+pub async fn blockies_png_image(req: HttpRequest) -> HttpResponse {
+    match parse_blockies_request(&req) {
+        Ok((address, size)) => HttpResponse::Ok()
+            .content_type("image/png")
+            .insert_header(cache_control())
+            .body(blockies_png(&address, size)),
+        Err(err) => HttpResponse::BadRequest().body(err),
+    }
+}
+
+pub async fn blockies_svg_image(req: HttpRequest) -> HttpResponse {
+    match parse_blockies_request(&req) {
+        Ok((address, size)) => HttpResponse::Ok()
+            .content_type("image/svg+xml")
+            .insert_header(cache_control())
+            .body(blockies_svg(&address, size)),
+        Err(err) => HttpResponse::BadRequest().body(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blockies_svg() {
+        let address = DbAddress::from_str("0xe686c14FF9C11038F2B1c9aD617F2346CFB817dC").unwrap();
+        let svg = blockies_svg(&address, 128);
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert!(svg.contains(r#"width="128""#));
+        assert_eq!(svg.matches("<rect").count(), 64);
+        // same address in any case gives the same image
+        let lower = DbAddress::from_str("0xe686c14ff9c11038f2b1c9ad617f2346cfb817dc").unwrap();
+        assert_eq!(blockies_svg(&lower, 128), svg);
+        assert!(blockies_png(&address, 64).starts_with(b"\x89PNG"));
+    }
+}
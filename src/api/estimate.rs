@@ -0,0 +1,54 @@
+use crate::fancy::{estimate_time_to_find, EstimateTarget};
+use crate::ServerData;
+use actix_web::web::Data;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::time::timeout;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateRequest {
+    pub target: EstimateTarget,
+    /// Runners to take live speed from, all runners if not given
+    pub runners: Option<Vec<usize>>,
+    /// Aggregate speed in MH/s, overrides speed reported by runners
+    pub speed_mhs: Option<f64>,
+}
+
+pub async fn estimate(
+    data: Data<Box<ServerData>>,
+    body: web::Json<EstimateRequest>,
+) -> HttpResponse {
+    let speed_mhs = if let Some(speed_mhs) = body.speed_mhs {
+        speed_mhs
+    } else {
+        let runner_nos = body
+            .runners
+            .clone()
+            .unwrap_or_else(|| (0..data.runners.len()).collect());
+        let mut speed_mhs = 0.0;
+        for runner_no in runner_nos {
+            let Some(runner) = data.runners.get(runner_no) else {
+                return HttpResponse::BadRequest()
+                    .body(format!("Invalid runner number {runner_no}"));
+            };
+            let runner = match timeout(Duration::from_secs(5), runner.lock()).await {
+                Ok(guard) => guard,
+                Err(_) => {
+                    return HttpResponse::RequestTimeout()
+                        .body("Timed out while waiting for runner lock");
+                }
+            };
+            if runner.is_started() {
+                speed_mhs += runner.reported_speed().unwrap_or(0.0);
+            }
+        }
+        speed_mhs
+    };
+
+    match estimate_time_to_find(&body.target, speed_mhs) {
+        Ok(estimate) => HttpResponse::Ok().json(estimate),
+        Err(err) => HttpResponse::BadRequest().body(err.to_string()),
+    }
+}
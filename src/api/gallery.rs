@@ -0,0 +1,82 @@
+use crate::db::ops::{get_fancy_obj, list_fancy_objs};
+use crate::fancy::{score_fancy, FancyDbObj, FancyScoreEntry};
+use crate::types::DbAddress;
+use crate::ServerData;
+use actix_web::web::Data;
+use actix_web::{HttpRequest, HttpResponse};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::utils::{extract_url_int_param, extract_url_param};
+
+const MAX_GALLERY_LIMIT: i64 = 500;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryItem {
+    #[serde(flatten)]
+    pub fancy: FancyDbObj,
+    pub address_mixed_case: String,
+    pub address_short_etherscan: String,
+    pub scores: BTreeMap<String, FancyScoreEntry>,
+    pub blockies_png: String,
+    pub blockies_svg: String,
+}
+
+impl GalleryItem {
+    pub fn new(fancy: FancyDbObj) -> Self {
+        let score = score_fancy(fancy.address.addr());
+        let address = score.address_lower_case;
+        Self {
+            fancy,
+            address_mixed_case: score.address_mixed_case,
+            address_short_etherscan: score.address_short_etherscan,
+            scores: score.scores,
+            blockies_png: format!("/api/address/{address}/blockies.png"),
+            blockies_svg: format!("/api/address/{address}/blockies.svg"),
+        }
+    }
+}
+
+pub async fn gallery(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let limit = extract_url_int_param(&request, "limit")?.unwrap_or(50);
+    let offset = extract_url_int_param(&request, "offset")?.unwrap_or(0);
+    let category = extract_url_param(&request, "category")?;
+    if !(1..=MAX_GALLERY_LIMIT).contains(&limit) || offset < 0 {
+        return Ok(HttpResponse::BadRequest().body(format!(
+            "Limit has to be between 1 and {MAX_GALLERY_LIMIT} and offset cannot be negative"
+        )));
+    }
+    let records = list_fancy_objs(&data.db, category.as_deref(), limit, offset)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(
+        records
+            .into_iter()
+            .map(GalleryItem::new)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+pub async fn gallery_item(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let address = request.match_info().query("address");
+    let address = match DbAddress::from_str(address) {
+        Ok(address) => address,
+        Err(err) => {
+            return Ok(HttpResponse::BadRequest().body(format!("Invalid address {address}: {err}")))
+        }
+    };
+    match get_fancy_obj(&data.db, address)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    {
+        Some(fancy) => Ok(HttpResponse::Ok().json(GalleryItem::new(fancy))),
+        None => Ok(HttpResponse::NotFound().body(format!("Address {address} is not stored"))),
+    }
+}
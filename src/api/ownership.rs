@@ -0,0 +1,69 @@
+use crate::db::model::OwnershipChange;
+use crate::db::ownership::{change_ownership, get_ownership_state, list_ownership_events};
+use crate::types::DbAddress;
+use crate::ServerData;
+use actix_web::web::{self, Data};
+use actix_web::{HttpRequest, HttpResponse};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipRequest {
+    /// Who makes the change, stored in audit trail
+    pub actor: String,
+    #[serde(flatten)]
+    pub change: OwnershipChange,
+}
+
+fn parse_address(request: &HttpRequest) -> Result<DbAddress, String> {
+    let address = request.match_info().query("address");
+    DbAddress::from_str(address).map_err(|err| format!("Invalid address {address}: {err}"))
+}
+
+pub async fn ownership_info(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let address = match parse_address(&request) {
+        Ok(address) => address,
+        Err(err) => return Ok(HttpResponse::BadRequest().body(err)),
+    };
+    let Some(state) = get_ownership_state(&data.db, address)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    else {
+        return Ok(HttpResponse::NotFound().body(format!("Address {address} is not stored")));
+    };
+    let events = list_ownership_events(&data.db, address)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(HttpResponse::Ok().json(json!({
+        "status": state.status(chrono::Utc::now().naive_utc()),
+        "state": state,
+        "events": events,
+    })))
+}
+
+/// Reserve, assign, release or transfer, rejected transitions return 409
+pub async fn ownership_change(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+    body: web::Json<OwnershipRequest>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let address = match parse_address(&request) {
+        Ok(address) => address,
+        Err(err) => return Ok(HttpResponse::BadRequest().body(err)),
+    };
+    if get_ownership_state(&data.db, address)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .is_none()
+    {
+        return Ok(HttpResponse::NotFound().body(format!("Address {address} is not stored")));
+    }
+    match change_ownership(&data.db, address, &body.change, &body.actor).await {
+        Ok(event) => Ok(HttpResponse::Ok().json(event)),
+        Err(err) => Ok(HttpResponse::Conflict().body(err.to_string())),
+    }
+}
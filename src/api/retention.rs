@@ -0,0 +1,49 @@
+use crate::config::get_config_opt;
+use crate::db::model::FancyQuery;
+use crate::db::ops::count_fancy_objs;
+use crate::db::retention::{count_archived, run_retention, RetentionPolicy};
+use crate::ServerData;
+use actix_web::error::ErrorInternalServerError;
+use actix_web::web::Data;
+use actix_web::HttpResponse;
+use serde_json::json;
+
+pub async fn retention_info(data: Data<Box<ServerData>>) -> Result<HttpResponse, actix_web::Error> {
+    let stored = count_fancy_objs(&data.db, &FancyQuery::default())
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let archived = count_archived(&data.db)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let stats = data.retention_stats.lock().clone();
+    Ok(HttpResponse::Ok().json(json!({
+        "config": get_config_opt().map(|config| &config.retention),
+        "stats": stats,
+        "stored": stored,
+        "archived": archived,
+    })))
+}
+
+/// Run retention now instead of waiting for periodic task, only when enabled in config
+pub async fn retention_run(data: Data<Box<ServerData>>) -> Result<HttpResponse, actix_web::Error> {
+    let Some(config) = get_config_opt()
+        .map(|config| config.retention.clone())
+        .filter(|config| config.enabled)
+    else {
+        return Ok(HttpResponse::BadRequest().body("Retention is not enabled in config"));
+    };
+    let policy = match RetentionPolicy::from_config(&config) {
+        Ok(policy) => policy,
+        Err(err) => return Ok(HttpResponse::BadRequest().body(err.to_string())),
+    };
+    let run = run_retention(&data.db, &policy)
+        .await
+        .map_err(ErrorInternalServerError)?;
+    let mut stats = data.retention_stats.lock();
+    stats.runs += 1;
+    stats.total_deleted += run.deleted as u64;
+    stats.total_archived += run.archived as u64;
+    stats.last_run = Some(run.clone());
+    stats.last_error = None;
+    Ok(HttpResponse::Ok().json(run))
+}
@@ -1,6 +1,8 @@
-use crate::api::utils::extract_url_int_param;
+use crate::api::utils::{extract_url_int_param, extract_url_param};
+use crate::config::get_runner_history_interval_secs;
 use crate::fancy::FancyDbObjMin;
-use crate::runner::WorkTarget;
+use crate::runner::{IngestionFilter, StopCondition, WorkTarget};
+use crate::runner_history::HistoryResolution;
 use crate::ServerData;
 use actix_web::web::Data;
 use actix_web::{web, HttpRequest, HttpResponse};
@@ -26,12 +28,55 @@ pub async fn list_runners(data: Data<Box<ServerData>>) -> HttpResponse {
             "enabled": runner.is_enabled(),
             "currentTarget": runner.current_target(),
             "workTarget": runner.work_target(),
+            "stopCondition": runner.stop_condition(),
+            "ingestionFilter": runner.ingestion_filter(),
             "queueLen": runner.queue_len(),
+            "queueSpilled": runner.queue_spilled_len(),
         }));
     }
     HttpResponse::Ok().json(runners)
 }
 
+/// Speed, compute, found count and queue length over time, resolution raw, 1m (default) or 1h.
+/// since is RFC 3339 timestamp
+pub async fn runner_history(
+    data: Data<Box<ServerData>>,
+    req: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let runner_no: usize = match req.match_info().query("runner_no").parse() {
+        Ok(num) => num,
+        Err(_) => return Ok(HttpResponse::BadRequest().body("Invalid runner number")),
+    };
+    let resolution = match extract_url_param(&req, "resolution")? {
+        Some(resolution) => match resolution.parse::<HistoryResolution>() {
+            Ok(resolution) => resolution,
+            Err(err) => return Ok(HttpResponse::BadRequest().body(err)),
+        },
+        None => HistoryResolution::Minute,
+    };
+    let since = match extract_url_param(&req, "since")? {
+        Some(since) => match chrono::DateTime::parse_from_rfc3339(&since) {
+            Ok(since) => Some(since.with_timezone(&chrono::Utc)),
+            Err(err) => return Ok(HttpResponse::BadRequest().body(format!("Invalid since: {err}"))),
+        },
+        None => None,
+    };
+    let history = data.runner_history.lock();
+    let Some(runner_history) = history.get(runner_no) else {
+        return Ok(HttpResponse::NotFound().body("Runner not found"));
+    };
+    let series = match resolution {
+        HistoryResolution::Raw => json!(runner_history.raw(since)),
+        _ => json!(runner_history.buckets(resolution, since)),
+    };
+    Ok(HttpResponse::Ok().json(json!({
+        "runnerNo": runner_no,
+        "resolution": resolution,
+        "intervalSecs": get_runner_history_interval_secs(),
+        "series": series,
+    })))
+}
+
 pub async fn start_benchmark(data: Data<Box<ServerData>>, req: HttpRequest) -> HttpResponse {
     let runner_no: usize = match req.match_info().query("runner_no").parse() {
         Ok(num) => num,
@@ -242,10 +287,14 @@ pub async fn kill(data: Data<Box<ServerData>>, req: HttpRequest) -> HttpResponse
     }
 }
 
+/// Targets cruncher cannot mine are refused, see WorkTarget::validate
 pub async fn set_runners_target(
     data: Data<Box<ServerData>>,
     wt: web::Json<WorkTarget>,
 ) -> HttpResponse {
+    if let Err(err) = wt.validate() {
+        return HttpResponse::BadRequest().body(format!("Invalid work target: {err}"));
+    }
     for runner in data.runners.iter() {
         let mut runner = match timeout(Duration::from_secs(5), runner.lock()).await {
             Ok(guard) => guard,
@@ -258,6 +307,87 @@ pub async fn set_runners_target(
     }
     HttpResponse::Ok().body("Target set to all runners")
 }
+
+pub async fn set_runners_stop_condition(
+    data: Data<Box<ServerData>>,
+    sc: web::Json<Option<StopCondition>>,
+) -> HttpResponse {
+    for runner in data.runners.iter() {
+        let mut runner = match timeout(Duration::from_secs(5), runner.lock()).await {
+            Ok(guard) => guard,
+            Err(_) => {
+                return HttpResponse::RequestTimeout()
+                    .body("Timed out while waiting for runner lock");
+            }
+        };
+        runner.set_stop_condition(sc.clone());
+    }
+    HttpResponse::Ok().body("Stop condition set to all runners")
+}
+/// Filter used for every target, target filter set later takes precedence
+pub async fn set_runners_ingestion_filter(
+    data: Data<Box<ServerData>>,
+    filter: web::Json<Option<IngestionFilter>>,
+) -> HttpResponse {
+    set_ingestion_filter(data, filter.into_inner(), false).await
+}
+
+/// Filter for current work target only, it is cleared when target changes
+pub async fn set_runners_target_ingestion_filter(
+    data: Data<Box<ServerData>>,
+    filter: web::Json<Option<IngestionFilter>>,
+) -> HttpResponse {
+    set_ingestion_filter(data, filter.into_inner(), true).await
+}
+
+async fn set_ingestion_filter(
+    data: Data<Box<ServerData>>,
+    filter: Option<IngestionFilter>,
+    for_target: bool,
+) -> HttpResponse {
+    if let Some(Err(err)) = filter.as_ref().map(|filter| filter.validate()) {
+        return HttpResponse::BadRequest().body(format!("Invalid ingestion filter: {err}"));
+    }
+    for runner in data.runners.iter() {
+        let mut runner = match timeout(Duration::from_secs(5), runner.lock()).await {
+            Ok(guard) => guard,
+            Err(_) => {
+                return HttpResponse::RequestTimeout()
+                    .body("Timed out while waiting for runner lock");
+            }
+        };
+        if for_target {
+            runner.set_target_ingestion_filter(filter.clone());
+        } else {
+            runner.set_ingestion_filter(filter.clone());
+        }
+    }
+    HttpResponse::Ok().body("Ingestion filter set to all runners, applied on next start")
+}
+
+/// Sampled results rejected by ingestion filter, for calibration
+pub async fn consume_rejected_samples(
+    data: Data<Box<ServerData>>,
+    request: HttpRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut limit = extract_url_int_param(&request, "limit")?.unwrap_or(1000);
+    let mut results = Vec::new();
+    for runner in data.runners.iter() {
+        let runner = match timeout(Duration::from_secs(5), runner.lock()).await {
+            Ok(guard) => guard,
+            Err(_) => {
+                return Ok(
+                    HttpResponse::RequestTimeout().body("Timed out while waiting for runner lock")
+                );
+            }
+        };
+        let samples = runner.consume_rejected_samples(limit.max(0) as usize);
+        limit -= samples.len() as i64;
+        results.extend(samples);
+    }
+    Ok(HttpResponse::Ok().json(results))
+}
+
 pub async fn consume_results_raw(
     data: Data<Box<ServerData>>,
     request: HttpRequest,
@@ -265,7 +395,7 @@ pub async fn consume_results_raw(
     let mut limit = extract_url_int_param(&request, "limit")?.unwrap_or(1000);
     let mut results: Vec<FancyDbObjMin> = Vec::new();
     for runner in data.runners.iter() {
-        let runner = match timeout(Duration::from_secs(5), runner.lock()).await {
+        let mut runner = match timeout(Duration::from_secs(5), runner.lock()).await {
             Ok(guard) => guard,
             Err(_) => {
                 return Ok(
@@ -275,12 +405,19 @@ pub async fn consume_results_raw(
         };
         let runner_results = runner.consume_results(limit as usize);
         limit -= runner_results.len() as i64;
+        if let Err(err) = runner.resume_if_drained().await {
+            log::error!("Failed to resume runner after consuming results: {err}");
+        }
         for res in runner_results.into_iter() {
             results.push(FancyDbObjMin {
                 address: res.address,
                 salt: res.salt,
                 factory: res.factory,
+                init_code_hash: res.init_code_hash,
                 public_key_base: res.public_key_base,
+                create3_scheme: res.create3_scheme,
+                caller: res.caller,
+                chain_id: res.chain_id,
             });
         }
     }
@@ -298,7 +435,7 @@ pub async fn consume_results(
     let mut limit = extract_url_int_param(&request, "limit")?.unwrap_or(1000);
     let mut results: Vec<FancyDbObjMin> = Vec::new();
     for runner in data.runners.iter() {
-        let runner = match timeout(Duration::from_secs(5), runner.lock()).await {
+        let mut runner = match timeout(Duration::from_secs(5), runner.lock()).await {
             Ok(guard) => guard,
             Err(_) => {
                 return Ok(
@@ -308,12 +445,19 @@ pub async fn consume_results(
         };
         let runner_results = runner.consume_results(limit as usize);
         limit -= runner_results.len() as i64;
+        if let Err(err) = runner.resume_if_drained().await {
+            log::error!("Failed to resume runner after consuming results: {err}");
+        }
         for res in runner_results.into_iter() {
             results.push(FancyDbObjMin {
                 address: res.address,
                 salt: res.salt,
                 factory: res.factory,
+                init_code_hash: res.init_code_hash,
                 public_key_base: res.public_key_base,
+                create3_scheme: res.create3_scheme,
+                caller: res.caller,
+                chain_id: res.chain_id,
             });
         }
     }
@@ -1,12 +1,23 @@
+use crate::api::addresses::{export_addresses, import_addresses, list_addresses};
+use crate::api::benchmarks::{benchmark_report, list_benchmarks, run_benchmark};
+use crate::api::blockies::{blockies_png_image, blockies_svg_image};
+use crate::api::estimate::estimate;
+use crate::api::gallery::{gallery, gallery_item};
 use crate::api::golem::{
     clean_yagna, configure_provider, get_all_historical_activity_info, get_last_exe_unit_log,
     provider_info, proxy_get_offers, start_provider, start_yagna, stop_provider, stop_yagna,
     yagna_info,
 };
+use crate::api::ownership::{ownership_change, ownership_info};
+use crate::api::retention::{retention_info, retention_run};
 use crate::api::runners::{
-    consume_results, consume_results_raw, disable, enable, kill, list_runners, runners_start,
-    runners_stop, set_runners_target, start, start_benchmark, stop,
+    consume_rejected_samples, consume_results, consume_results_raw, disable, enable, kill,
+    list_runners, runner_history, runners_start, runners_stop, set_runners_ingestion_filter,
+    set_runners_stop_condition, set_runners_target, set_runners_target_ingestion_filter, start,
+    start_benchmark, stop,
 };
+use crate::api::score::{price_preview, score_address, score_addresses, score_categories};
+use crate::config::get_import_max_size;
 use actix_web::{web, Scope};
 
 #[rustfmt::skip]
@@ -14,16 +25,44 @@ pub fn server_api_scope() -> Scope {
     Scope::new("/api")
         .route("/runners", web::get().to(list_runners))
         .route("/runner/{runner_no}/start", web::post().to(start))
+        .route("/runner/{runner_no}/history", web::get().to(runner_history))
         .route("/runner/{runner_no}/benchmark/start", web::post().to(start_benchmark))
         .route("/runner/{runner_no}/stop", web::post().to(stop))
         .route("/runner/{runner_no}/kill", web::post().to(kill))
         .route("/runner/{runner_no}/enable", web::post().to(enable))
         .route("/runner/{runner_no}/disable", web::post().to(disable))
         .route("/runners/target/set", web::post().to(set_runners_target))
+        .route("/runners/stop-condition/set", web::post().to(set_runners_stop_condition))
         .route("/runners/results/consume", web::post().to(consume_results))
         .route("/runners/results/consume/raw", web::post().to(consume_results_raw))
+        .route("/runners/ingestion-filter/set", web::post().to(set_runners_ingestion_filter))
+        .route("/runners/target/ingestion-filter/set", web::post().to(set_runners_target_ingestion_filter))
+        .route("/runners/rejected/consume", web::post().to(consume_rejected_samples))
         .route("/runners/start", web::post().to(runners_start))
+        .route("/benchmarks", web::get().to(list_benchmarks))
+        .route("/benchmarks/run", web::post().to(run_benchmark))
+        .route("/benchmarks/{id}", web::get().to(benchmark_report))
         .route("/runners/stop", web::post().to(runners_stop))
+        .route("/estimate", web::post().to(estimate))
+        .route("/price/preview", web::post().to(price_preview))
+        .route("/score/categories", web::get().to(score_categories))
+        .route("/score/{address}", web::get().to(score_address))
+        .route("/score", web::post().to(score_addresses))
+        .route("/addresses", web::get().to(list_addresses))
+        .route("/addresses/export", web::get().to(export_addresses))
+        .service(
+            web::resource("/addresses/import")
+                .app_data(web::PayloadConfig::new(get_import_max_size()))
+                .route(web::post().to(import_addresses)),
+        )
+        .route("/address/{address}", web::get().to(gallery_item))
+        .route("/address/{address}/blockies.png", web::get().to(blockies_png_image))
+        .route("/address/{address}/blockies.svg", web::get().to(blockies_svg_image))
+        .route("/address/{address}/ownership", web::get().to(ownership_info))
+        .route("/address/{address}/ownership", web::post().to(ownership_change))
+        .route("/gallery", web::get().to(gallery))
+        .route("/retention", web::get().to(retention_info))
+        .route("/retention/run", web::post().to(retention_run))
         .route("/yagna/start", web::post().to(start_yagna))
         .route("/yagna/info", web::get().to(yagna_info))
         .route("/provider/start", web::post().to(start_provider))
@@ -0,0 +1,75 @@
+use crate::fancy::{explain_score, list_score_categories, score_fancy, Pricing, PricingConfig};
+use crate::types::DbAddress;
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Batch requests are computed in request handler, so keep them reasonably small
+const MAX_SCORE_BATCH: usize = 1000;
+
+pub async fn score_address(req: HttpRequest) -> HttpResponse {
+    let address = req.match_info().query("address");
+    match DbAddress::from_str(address) {
+        Ok(address) => HttpResponse::Ok().json(explain_score(address.addr())),
+        Err(err) => HttpResponse::BadRequest().body(format!("Invalid address {address}: {err}")),
+    }
+}
+
+pub async fn score_addresses(addresses: web::Json<Vec<String>>) -> HttpResponse {
+    if addresses.len() > MAX_SCORE_BATCH {
+        return HttpResponse::BadRequest()
+            .body(format!("Too many addresses, maximum is {MAX_SCORE_BATCH}"));
+    }
+    let mut explanations = Vec::with_capacity(addresses.len());
+    for address in addresses.iter() {
+        match DbAddress::from_str(address) {
+            Ok(address) => explanations.push(explain_score(address.addr())),
+            Err(err) => {
+                return HttpResponse::BadRequest().body(format!("Invalid address {address}: {err}"))
+            }
+        }
+    }
+    HttpResponse::Ok().json(explanations)
+}
+
+pub async fn score_categories() -> HttpResponse {
+    HttpResponse::Ok().json(list_score_categories())
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PricePreviewRequest {
+    pub address: String,
+    /// Pricing config to try, configured pricing is used when not given
+    pub pricing: Option<PricingConfig>,
+}
+
+pub async fn price_preview(body: web::Json<PricePreviewRequest>) -> HttpResponse {
+    let address = match DbAddress::from_str(&body.address) {
+        Ok(address) => address,
+        Err(err) => {
+            return HttpResponse::BadRequest()
+                .body(format!("Invalid address {}: {err}", body.address))
+        }
+    };
+    let pricing = match &body.pricing {
+        Some(config) => match Pricing::from_config(config) {
+            Ok(pricing) => pricing,
+            Err(err) => return HttpResponse::BadRequest().body(err.to_string()),
+        },
+        None => match Pricing::from_config(&crate::config::get_config().pricing) {
+            Ok(pricing) => pricing,
+            Err(err) => return HttpResponse::InternalServerError().body(err.to_string()),
+        },
+    };
+    let score = score_fancy(address.addr());
+    HttpResponse::Ok().json(json!({
+        "address": score.address_mixed_case,
+        "category": score.category,
+        "difficulty": score.total_score,
+        "baseDifficulty": pricing.base_difficulty,
+        "basePrice": pricing.base_price,
+        "priceMultiplier": pricing.price_multiplier(&score.category, score.total_score),
+        "price": pricing.price(&score),
+    }))
+}
@@ -40,6 +40,23 @@ pub fn extract_url_int_param(
     }
 }
 
+pub fn extract_url_float_param(
+    request: &HttpRequest,
+    param: &str,
+) -> Result<Option<f64>, actix_web::Error> {
+    if let Some(str) = extract_url_param(request, param)? {
+        match str.parse::<f64>() {
+            Ok(val) if val.is_finite() => Ok(Some(val)),
+            _ => Err(actix_web::error::ErrorBadRequest(format!(
+                "Failed to parse {} as number",
+                param
+            ))),
+        }
+    } else {
+        Ok(None)
+    }
+}
+
 #[allow(unused)]
 //probably nice to make generic version of this, but for now i64 is enough
 pub fn extract_url_bool_param(
@@ -0,0 +1,315 @@
+use crate::db::benchmark::{
+    compare_benchmark_report, insert_benchmark_report, BenchmarkReport, ComparedBenchmarkReport,
+    SpeedSample, SpeedSamples,
+};
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use crate::runner::{CrunchRunner, WorkTarget};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// Speed is considered settled once it reaches this fraction of steady speed
+const WARMUP_THRESHOLD: f64 = 0.9;
+
+fn default_duration_secs() -> f64 {
+    60.0
+}
+
+fn default_sample_interval_secs() -> f64 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkRequest {
+    /// Runner numbers to benchmark, all runners when not given
+    pub runners: Option<Vec<usize>>,
+    #[serde(default = "default_duration_secs")]
+    pub duration_secs: f64,
+    #[serde(default = "default_sample_interval_secs")]
+    pub sample_interval_secs: f64,
+    /// Target used for benchmark, runner work target when not given
+    pub target: Option<WorkTarget>,
+    /// Driver or cruncher version stored with report
+    pub tool_version: Option<String>,
+}
+
+impl BenchmarkRequest {
+    pub fn validate(&self) -> Result<(), AddressologyError> {
+        if self.duration_secs <= 0.0 {
+            return Err(err_custom_create!("Benchmark duration has to be positive"));
+        }
+        if self.sample_interval_secs <= 0.0 || self.sample_interval_secs > self.duration_secs {
+            return Err(err_custom_create!(
+                "Sample interval has to be positive and not longer than duration"
+            ));
+        }
+        if let Some(target) = &self.target {
+            target.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Selected runner numbers, checked against number of available runners
+    pub fn runner_numbers(&self, available: usize) -> Result<Vec<usize>, AddressologyError> {
+        let selected = self
+            .runners
+            .clone()
+            .unwrap_or_else(|| (0..available).collect());
+        if let Some(missing) = selected.iter().find(|runner_no| **runner_no >= available) {
+            return Err(err_custom_create!("Runner {missing} not found"));
+        }
+        if selected.is_empty() {
+            return Err(err_custom_create!("No runners to benchmark"));
+        }
+        Ok(selected)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkOutcome {
+    pub runner_no: usize,
+    pub report: Option<ComparedBenchmarkReport>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedSummary {
+    pub mean: f64,
+    pub stddev: f64,
+    pub warmup_secs: Option<f64>,
+}
+
+/// Steady speed is median of second half of samples, warm-up ends at first sample
+/// reaching 90% of it. Mean and stddev are computed from samples after warm-up
+pub fn summarize_speed_samples(samples: &[SpeedSample]) -> SpeedSummary {
+    if samples.is_empty() {
+        return SpeedSummary {
+            mean: 0.0,
+            stddev: 0.0,
+            warmup_secs: None,
+        };
+    }
+    let mut tail = samples[samples.len() / 2..]
+        .iter()
+        .map(|sample| sample.speed)
+        .collect::<Vec<_>>();
+    tail.sort_by(|a, b| a.total_cmp(b));
+    let steady = tail[tail.len() / 2];
+    let warmup_idx = samples
+        .iter()
+        .position(|sample| sample.speed >= steady * WARMUP_THRESHOLD);
+    let settled = &samples[warmup_idx.unwrap_or(0)..];
+    let mean = settled.iter().map(|sample| sample.speed).sum::<f64>() / settled.len() as f64;
+    let stddev = if settled.len() > 1 {
+        (settled
+            .iter()
+            .map(|sample| (sample.speed - mean).powi(2))
+            .sum::<f64>()
+            / (settled.len() - 1) as f64)
+            .sqrt()
+    } else {
+        0.0
+    };
+    SpeedSummary {
+        mean,
+        stddev,
+        warmup_secs: warmup_idx.map(|idx| samples[idx].elapsed_secs),
+    }
+}
+
+/// Runs cruncher in benchmark mode and samples its reported speed.
+/// Runner lock is held only for start/stop, so runner stays visible in API meanwhile.
+/// Fails when no speed was sampled, so empty runs do not show up as regressions
+pub async fn run_benchmark(
+    runner: Arc<tokio::sync::Mutex<CrunchRunner>>,
+    runner_no: usize,
+    request: &BenchmarkRequest,
+) -> Result<BenchmarkReport, AddressologyError> {
+    request.validate()?;
+    let (previous_target, target) = {
+        let mut runner = runner.lock().await;
+        if runner.is_started() {
+            return Err(err_custom_create!(
+                "Runner {runner_no} is busy, stop it before benchmark"
+            ));
+        }
+        let previous_target = runner.work_target();
+        let target = request.target.clone().unwrap_or(previous_target.clone());
+        runner.set_target(target.clone());
+        let started = runner.start(Some(request.duration_secs)).await;
+        if let Err(err) = started {
+            runner.set_target(previous_target);
+            return Err(err);
+        }
+        (previous_target, target)
+    };
+    log::info!(
+        "Benchmarking runner {runner_no} on {} for {}s",
+        target.kind(),
+        request.duration_secs
+    );
+
+    let started_at = Instant::now();
+    let started_utc = chrono::Utc::now();
+    let mut samples = Vec::new();
+    let mut last_update = None;
+    let mut device_name = None;
+    while started_at.elapsed().as_secs_f64() < request.duration_secs {
+        sleep(Duration::from_secs_f64(request.sample_interval_secs)).await;
+        let runner = runner.lock().await;
+        device_name = runner.device_name().or(device_name);
+        let updated = runner.last_updated_speed();
+        // speed left from previous run is not sampled
+        if updated.is_some_and(|updated| updated > started_utc) && updated != last_update {
+            last_update = updated;
+            if let Some(speed) = runner.reported_speed() {
+                samples.push(SpeedSample {
+                    elapsed_secs: started_at.elapsed().as_secs_f64(),
+                    speed,
+                });
+            }
+        }
+        if !runner.is_started() {
+            break;
+        }
+    }
+
+    {
+        let mut runner = runner.lock().await;
+        runner.stop().await?;
+        runner.set_target(previous_target);
+    }
+    if samples.is_empty() {
+        return Err(err_custom_create!(
+            "Runner {runner_no} reported no speed during benchmark, report is not stored"
+        ));
+    }
+    let summary = summarize_speed_samples(&samples);
+    log::info!(
+        "Benchmark of runner {runner_no} finished: {} samples, mean {:.2} MH/s",
+        samples.len(),
+        summary.mean
+    );
+    Ok(BenchmarkReport {
+        id: 0,
+        runner_no: runner_no as i64,
+        device_name,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        tool_version: request.tool_version.clone(),
+        target_kind: target.kind().to_string(),
+        duration_secs: started_at.elapsed().as_secs_f64(),
+        sample_count: samples.len() as i64,
+        mean_speed: summary.mean,
+        stddev_speed: summary.stddev,
+        warmup_secs: summary.warmup_secs,
+        speed_samples: SpeedSamples(samples),
+        created: started_utc.naive_utc(),
+    })
+}
+
+/// Benchmark selected runners at the same time, store reports and compare with previous runs
+pub async fn run_benchmarks(
+    conn: &SqlitePool,
+    runners: &[Arc<tokio::sync::Mutex<CrunchRunner>>],
+    request: &BenchmarkRequest,
+) -> Result<Vec<BenchmarkOutcome>, AddressologyError> {
+    request.validate()?;
+    let selected = request.runner_numbers(runners.len())?;
+    let runs = selected.iter().map(|runner_no| async move {
+        let report = match run_benchmark(runners[*runner_no].clone(), *runner_no, request).await {
+            Ok(report) => match insert_benchmark_report(conn, &report).await {
+                Ok(stored) => compare_benchmark_report(conn, stored).await,
+                Err(err) => Err(err),
+            },
+            Err(err) => Err(err),
+        };
+        match report {
+            Ok(report) => {
+                if let Some(comparison) = report.comparison.as_ref().filter(|c| c.regression) {
+                    log::warn!(
+                        "Benchmark regression on runner {runner_no}: {:.1}% against report {}",
+                        comparison.change_percent,
+                        comparison.previous_id
+                    );
+                }
+                BenchmarkOutcome {
+                    runner_no: *runner_no,
+                    report: Some(report),
+                    error: None,
+                }
+            }
+            Err(err) => {
+                log::error!("Benchmark of runner {runner_no} failed: {err}");
+                BenchmarkOutcome {
+                    runner_no: *runner_no,
+                    report: None,
+                    error: Some(err.to_string()),
+                }
+            }
+        }
+    });
+    Ok(join_all(runs).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(speeds: &[f64]) -> Vec<SpeedSample> {
+        speeds
+            .iter()
+            .enumerate()
+            .map(|(idx, speed)| SpeedSample {
+                elapsed_secs: (idx + 1) as f64,
+                speed: *speed,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_summarize_speed_samples() {
+        let summary =
+            summarize_speed_samples(&samples(&[10.0, 50.0, 95.0, 100.0, 102.0, 98.0, 100.0]));
+        assert_eq!(summary.warmup_secs, Some(3.0));
+        assert!((summary.mean - 99.0).abs() < 1e-9);
+        assert!((summary.stddev - 7.0f64.sqrt()).abs() < 1e-9);
+
+        let steady = summarize_speed_samples(&samples(&[100.0, 100.0, 100.0]));
+        assert_eq!(steady.warmup_secs, Some(1.0));
+        assert_eq!(steady.mean, 100.0);
+        assert_eq!(steady.stddev, 0.0);
+
+        let empty = summarize_speed_samples(&[]);
+        assert_eq!(empty.warmup_secs, None);
+        assert_eq!(empty.mean, 0.0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_benchmark_without_samples() {
+        // process exits at once without reporting any speed
+        let runner = Arc::new(tokio::sync::Mutex::new(CrunchRunner::new(
+            "/bin/true".parse().unwrap(),
+            0,
+        )));
+        let request = BenchmarkRequest {
+            runners: None,
+            duration_secs: 0.5,
+            sample_interval_secs: 0.1,
+            target: None,
+            tool_version: None,
+        };
+        let err = run_benchmark(runner.clone(), 0, &request)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no speed"));
+        assert!(!runner.lock().await.is_started());
+    }
+}
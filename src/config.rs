@@ -1,3 +1,7 @@
+use crate::db::retention::RetentionConfig;
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use crate::fancy::{Pricing, PricingConfig};
 use rand::distr::Alphanumeric;
 use rand::{rng, Rng};
 use serde::{Deserialize, Serialize};
@@ -16,12 +20,17 @@ fn get_random_string(length: usize) -> String {
         .map(char::from)
         .collect()
 }
-pub fn initialize_config() {
-    let config = ApplicationConfig::load_conf();
+pub fn initialize_config() -> Result<(), AddressologyError> {
+    let config = ApplicationConfig::load_conf()?;
 
     CONFIG
         .set(Some(config))
         .expect("Config can only be set once");
+    Ok(())
+}
+/// Config if it was initialized, library code and tests can run without it
+pub fn get_config_opt() -> Option<&'static ApplicationConfig> {
+    CONFIG.get().and_then(|config| config.as_ref())
 }
 pub fn get_config() -> &'static ApplicationConfig {
     CONFIG
@@ -46,6 +55,10 @@ pub struct ApplicationConfig {
     pub price_automatically: bool,
     pub auto_update: bool,
     pub central_net_host: Option<String>,
+    #[serde(default)]
+    pub pricing: PricingConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
 }
 
 impl Default for ApplicationConfig {
@@ -63,6 +76,8 @@ impl Default for ApplicationConfig {
             price_automatically: false,
             auto_update: false,
             central_net_host: Some("polygongas.org:7999".to_string()),
+            pricing: PricingConfig::default(),
+            retention: RetentionConfig::default(),
         }
     }
 }
@@ -90,7 +105,8 @@ impl ApplicationConfig {
             .expect("Failed to write config file");
     }
 
-    pub fn load_conf() -> ApplicationConfig {
+    /// Invalid pricing section is an error, addresses would be sold at unexpected prices otherwise
+    pub fn load_conf() -> Result<ApplicationConfig, AddressologyError> {
         let config_file_path = env::var("CONFIG_CLIENT_PATH").unwrap_or("config.toml".to_string());
         let mut file = File::open(&config_file_path);
         let config = match file.as_mut() {
@@ -99,7 +115,10 @@ impl ApplicationConfig {
                 file.read_to_string(&mut str)
                     .expect("Config file has to be readable");
                 let config: ApplicationConfig = toml::de::from_str::<ApplicationConfig>(&str)
-                    .unwrap_or(ApplicationConfig::default());
+                    .unwrap_or_else(|err| {
+                        log::error!("Failed to parse {config_file_path}, using defaults: {err}");
+                        ApplicationConfig::default()
+                    });
                 config
             }
             Err(_) => {
@@ -108,21 +127,30 @@ impl ApplicationConfig {
                 default_config
             }
         };
+        Pricing::from_config(&config.pricing).map_err(|err| {
+            err_custom_create!("Invalid pricing section in {config_file_path}: {err}")
+        })?;
         config.save_to_toml();
-        config
+        Ok(config)
+    }
+}
+
+fn get_env_parsed<T: FromStr>(key: &str, default: T) -> T {
+    match env::var(key) {
+        Ok(value) => T::from_str(value.trim()).unwrap_or_else(|_| {
+            log::error!("Invalid value {value} of env {key}, using default");
+            default
+        }),
+        Err(_) => default,
     }
 }
 
 fn get_env_int(key: &str, default: i64) -> i64 {
-    env::var(key)
-        .map(|s| i64::from_str(&s).unwrap())
-        .unwrap_or(default)
+    get_env_parsed(key, default)
 }
 
 fn get_env_float(key: &str, default: f64) -> f64 {
-    env::var(key)
-        .map(|s| f64::from_str(&s).unwrap())
-        .unwrap_or(default)
+    get_env_parsed(key, default)
 }
 
 pub fn get_base_difficulty() -> f64 {
@@ -132,3 +160,62 @@ pub fn get_base_difficulty() -> f64 {
 pub fn get_base_difficulty_price() -> i64 {
     get_env_int("BASE_DIFFICULTY_PRICE", 1000)
 }
+
+pub fn get_hexspeak_word_list_path() -> Option<String> {
+    env::var("HEXSPEAK_WORD_LIST").ok()
+}
+
+/// Blockies are derived from address only, so by default they can be cached for a year
+pub fn get_blockies_cache_max_age() -> i64 {
+    get_env_int("BLOCKIES_CACHE_MAX_AGE", 365 * 24 * 3600)
+}
+
+/// Largest body accepted by import endpoint, raw dumps and old exports can be big
+pub fn get_import_max_size() -> usize {
+    get_env_int("IMPORT_MAX_SIZE", 256 * 1024 * 1024).max(1) as usize
+}
+
+/// Reservation is a promise to a prospect, it should not block the address for too long
+pub fn get_max_reservation_secs() -> i64 {
+    get_env_int("MAX_RESERVATION_SECS", 30 * 24 * 3600)
+}
+
+/// Results kept in memory per runner, the rest is spilled to disk (0 spills everything)
+pub fn get_result_queue_memory_cap() -> usize {
+    get_env_int("RESULT_QUEUE_MEMORY_CAP", 10000).max(0) as usize
+}
+
+/// Cruncher is paused when its unconsumed results exceed this count
+/// and new results are not getting into database
+pub fn get_result_queue_hard_limit() -> usize {
+    get_env_int("RESULT_QUEUE_HARD_LIMIT", 1_000_000).max(1) as usize
+}
+
+/// How often runners paused for result backlog are checked for resume
+pub fn get_result_queue_resume_interval_secs() -> u64 {
+    get_env_int("RESULT_QUEUE_RESUME_INTERVAL_SECS", 10).max(1) as u64
+}
+
+/// Found addresses waiting to be stored in database, results over it are kept only in queue
+pub fn get_result_sink_capacity() -> usize {
+    get_env_int("RESULT_SINK_CAPACITY", 10000).max(1) as usize
+}
+
+pub fn get_result_queue_spill_dir() -> String {
+    env::var("RESULT_QUEUE_SPILL_DIR").unwrap_or("result-queue".to_string())
+}
+
+/// Drop of mean benchmark speed (in percent) against previous run reported as regression
+pub fn get_benchmark_regression_percent() -> f64 {
+    get_env_float("BENCHMARK_REGRESSION_PERCENT", 5.0)
+}
+
+/// How often speed, compute, found count and queue length of each runner are sampled
+pub fn get_runner_history_interval_secs() -> u64 {
+    get_env_int("RUNNER_HISTORY_INTERVAL_SECS", 10).max(1) as u64
+}
+
+/// Runner history is kept only in memory when not set
+pub fn get_runner_history_dir() -> Option<String> {
+    env::var("RUNNER_HISTORY_DIR").ok()
+}
@@ -0,0 +1,9 @@
+pub mod benchmark;
+pub mod connection;
+pub mod export;
+pub mod import;
+pub mod model;
+pub mod ops;
+pub mod ownership;
+pub mod retention;
+pub mod store;
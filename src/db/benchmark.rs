@@ -0,0 +1,235 @@
+use crate::config::get_benchmark_regression_percent;
+use crate::error::{AddressologyError, ErrorBag};
+use crate::{err_custom_create, err_from};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedSample {
+    /// Seconds since benchmark start
+    pub elapsed_secs: f64,
+    /// MH/s as reported by cruncher
+    pub speed: f64,
+}
+
+/// Samples are stored as json text in one column
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(transparent)]
+pub struct SpeedSamples(pub Vec<SpeedSample>);
+
+impl TryFrom<String> for SpeedSamples {
+    type Error = serde_json::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        serde_json::from_str(&value)
+    }
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    pub id: i64,
+    pub runner_no: i64,
+    pub device_name: Option<String>,
+    pub app_version: String,
+    /// Driver or cruncher version given by operator
+    pub tool_version: Option<String>,
+    pub target_kind: String,
+    pub duration_secs: f64,
+    pub sample_count: i64,
+    pub mean_speed: f64,
+    pub stddev_speed: f64,
+    /// Time until speed settled, None when it never did
+    pub warmup_secs: Option<f64>,
+    #[sqlx(try_from = "String")]
+    pub speed_samples: SpeedSamples,
+    pub created: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkComparison {
+    pub previous_id: i64,
+    pub previous_mean_speed: f64,
+    pub previous_tool_version: Option<String>,
+    pub change_percent: f64,
+    pub regression: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparedBenchmarkReport {
+    #[serde(flatten)]
+    pub report: BenchmarkReport,
+    pub comparison: Option<BenchmarkComparison>,
+}
+
+/// Report is stored with id and created set by database
+pub async fn insert_benchmark_report(
+    conn: &SqlitePool,
+    report: &BenchmarkReport,
+) -> Result<BenchmarkReport, AddressologyError> {
+    let speed_samples = serde_json::to_string(&report.speed_samples)
+        .map_err(|e| err_custom_create!("Failed to serialize speed samples: {e}"))?;
+    sqlx::query_as::<_, BenchmarkReport>(
+        r"INSERT INTO benchmark_report
+        (runner_no, device_name, app_version, tool_version, target_kind, duration_secs,
+         sample_count, mean_speed, stddev_speed, warmup_secs, speed_samples, created)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) RETURNING *",
+    )
+    .bind(report.runner_no)
+    .bind(&report.device_name)
+    .bind(&report.app_version)
+    .bind(&report.tool_version)
+    .bind(&report.target_kind)
+    .bind(report.duration_secs)
+    .bind(report.sample_count)
+    .bind(report.mean_speed)
+    .bind(report.stddev_speed)
+    .bind(report.warmup_secs)
+    .bind(speed_samples)
+    .bind(chrono::Utc::now().naive_utc())
+    .fetch_one(conn)
+    .await
+    .map_err(err_from!())
+}
+
+pub async fn get_benchmark_report(
+    conn: &SqlitePool,
+    id: i64,
+) -> Result<Option<BenchmarkReport>, AddressologyError> {
+    sqlx::query_as::<_, BenchmarkReport>(r"SELECT * FROM benchmark_report WHERE id = $1")
+        .bind(id)
+        .fetch_optional(conn)
+        .await
+        .map_err(err_from!())
+}
+
+/// Newest first, optionally only for one device
+pub async fn list_benchmark_reports(
+    conn: &SqlitePool,
+    device_name: Option<&str>,
+    limit: i64,
+) -> Result<Vec<BenchmarkReport>, AddressologyError> {
+    sqlx::query_as::<_, BenchmarkReport>(
+        r"SELECT * FROM benchmark_report WHERE ($1 IS NULL OR device_name = $1)
+        ORDER BY id DESC LIMIT $2",
+    )
+    .bind(device_name)
+    .bind(limit)
+    .fetch_all(conn)
+    .await
+    .map_err(err_from!())
+}
+
+/// Compare with the previous run on the same device and target kind
+pub async fn compare_benchmark_report(
+    conn: &SqlitePool,
+    report: BenchmarkReport,
+) -> Result<ComparedBenchmarkReport, AddressologyError> {
+    let previous = sqlx::query_as::<_, BenchmarkReport>(
+        r"SELECT * FROM benchmark_report
+        WHERE device_name IS $1 AND target_kind = $2 AND id < $3 AND sample_count > 0
+        ORDER BY id DESC LIMIT 1",
+    )
+    .bind(&report.device_name)
+    .bind(&report.target_kind)
+    .bind(report.id)
+    .fetch_optional(conn)
+    .await
+    .map_err(err_from!())?;
+    let comparison = previous
+        .filter(|previous| previous.mean_speed > 0.0)
+        .map(|previous| {
+            let change_percent =
+                (report.mean_speed - previous.mean_speed) / previous.mean_speed * 100.0;
+            BenchmarkComparison {
+                previous_id: previous.id,
+                previous_mean_speed: previous.mean_speed,
+                previous_tool_version: previous.tool_version,
+                change_percent,
+                regression: change_percent < -get_benchmark_regression_percent(),
+            }
+        });
+    Ok(ComparedBenchmarkReport { report, comparison })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::create_sqlite_connection;
+
+    fn report(device_name: &str, tool_version: &str, mean_speed: f64) -> BenchmarkReport {
+        BenchmarkReport {
+            id: 0,
+            runner_no: 0,
+            device_name: Some(device_name.to_string()),
+            app_version: "0.1.1".to_string(),
+            tool_version: Some(tool_version.to_string()),
+            target_kind: "factory".to_string(),
+            duration_secs: 60.0,
+            sample_count: 2,
+            mean_speed,
+            stddev_speed: 1.0,
+            warmup_secs: Some(5.0),
+            speed_samples: SpeedSamples(vec![
+                SpeedSample {
+                    elapsed_secs: 1.0,
+                    speed: mean_speed - 1.0,
+                },
+                SpeedSample {
+                    elapsed_secs: 2.0,
+                    speed: mean_speed + 1.0,
+                },
+            ]),
+            created: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_reports() {
+        let conn = create_sqlite_connection(None, true).await.unwrap();
+        let first = insert_benchmark_report(&conn, &report("RTX 4090", "551.23", 1000.0))
+            .await
+            .unwrap();
+        assert!(first.id > 0);
+        assert_eq!(first.speed_samples.0.len(), 2);
+        let compared = compare_benchmark_report(&conn, first.clone())
+            .await
+            .unwrap();
+        assert!(compared.comparison.is_none());
+
+        insert_benchmark_report(&conn, &report("RTX 3060", "551.23", 300.0))
+            .await
+            .unwrap();
+        let second = insert_benchmark_report(&conn, &report("RTX 4090", "560.10", 900.0))
+            .await
+            .unwrap();
+        let comparison = compare_benchmark_report(&conn, second)
+            .await
+            .unwrap()
+            .comparison
+            .unwrap();
+        assert_eq!(comparison.previous_id, first.id);
+        assert_eq!(comparison.previous_tool_version.as_deref(), Some("551.23"));
+        assert!((comparison.change_percent + 10.0).abs() < 1e-9);
+        assert!(comparison.regression);
+
+        let all = list_benchmark_reports(&conn, None, 10).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].id > all[1].id);
+        assert_eq!(
+            list_benchmark_reports(&conn, Some("RTX 4090"), 10)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            get_benchmark_report(&conn, first.id).await.unwrap(),
+            Some(first)
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use crate::err_from;
+use crate::error::{AddressologyError, ErrorBag};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::path::Path;
+use std::str::FromStr;
+
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Open (or create) sqlite database, None opens private in-memory database used in tests
+pub async fn create_sqlite_connection(
+    db_filename: Option<&Path>,
+    run_migrations: bool,
+) -> Result<SqlitePool, AddressologyError> {
+    let (url, max_connections) = match db_filename {
+        Some(db_filename) => (format!("sqlite://{}", db_filename.display()), 5),
+        // every in-memory connection is a separate database
+        None => ("sqlite::memory:".to_string(), 1),
+    };
+    let options = SqliteConnectOptions::from_str(&url)
+        .map_err(err_from!())?
+        .journal_mode(SqliteJournalMode::Wal)
+        .create_if_missing(true)
+        .with_regexp();
+    let pool = SqlitePoolOptions::new()
+        .max_connections(max_connections)
+        .connect_with(options)
+        .await
+        .map_err(err_from!())?;
+    if run_migrations {
+        MIGRATOR.run(&pool).await.map_err(err_from!())?;
+    }
+    Ok(pool)
+}
@@ -0,0 +1,329 @@
+use crate::db::model::{FancyCursor, FancyQuery};
+use crate::db::ops::query_fancy_objs;
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use crate::fancy::{score_fancy, FancyDbObj, SCORED_CATEGORIES};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sqlx::SqlitePool;
+use std::str::FromStr;
+
+/// Rows are read in pages of this size, so export never holds the whole table
+pub const EXPORT_BATCH_SIZE: i64 = 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Jsonl => "application/jsonl",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "jsonl" => Ok(ExportFormat::Jsonl),
+            _ => Err(format!("Unknown export format {s}, use csv or jsonl")),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportColumn {
+    Address,
+    AddressMixedCase,
+    AddressShortEtherscan,
+    Salt,
+    Factory,
+    InitCodeHash,
+    PublicKeyBase,
+    Create3Scheme,
+    Caller,
+    ChainId,
+    Created,
+    Score,
+    Price,
+    Category,
+    Owner,
+    Job,
+    /// Difficulty in every category, one column per category in csv
+    Scores,
+}
+
+/// Default export has every input needed to recompute and verify the record
+pub const DEFAULT_EXPORT_COLUMNS: [ExportColumn; 12] = [
+    ExportColumn::Address,
+    ExportColumn::AddressMixedCase,
+    ExportColumn::Salt,
+    ExportColumn::Factory,
+    ExportColumn::InitCodeHash,
+    ExportColumn::PublicKeyBase,
+    ExportColumn::Create3Scheme,
+    ExportColumn::Caller,
+    ExportColumn::ChainId,
+    ExportColumn::Score,
+    ExportColumn::Price,
+    ExportColumn::Category,
+];
+
+impl ExportColumn {
+    fn name(&self) -> String {
+        json!(self).as_str().unwrap_or_default().to_string()
+    }
+}
+
+impl FromStr for ExportColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(Value::String(s.to_string()))
+            .map_err(|_| format!("Unknown export column {s}"))
+    }
+}
+
+/// Comma separated column names, empty string means default columns
+pub fn parse_export_columns(columns: &str) -> Result<Vec<ExportColumn>, AddressologyError> {
+    if columns.trim().is_empty() {
+        return Ok(DEFAULT_EXPORT_COLUMNS.to_vec());
+    }
+    columns
+        .split(',')
+        .map(|column| ExportColumn::from_str(column.trim()).map_err(|e| err_custom_create!("{e}")))
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn export_header(format: ExportFormat, columns: &[ExportColumn]) -> String {
+    match format {
+        ExportFormat::Csv => {
+            let mut names = Vec::new();
+            for column in columns {
+                match column {
+                    ExportColumn::Scores => names.extend(
+                        SCORED_CATEGORIES
+                            .iter()
+                            .map(|category| format!("scores.{category}")),
+                    ),
+                    column => names.push(column.name()),
+                }
+            }
+            names.join(",") + "\n"
+        }
+        ExportFormat::Jsonl => String::new(),
+    }
+}
+
+fn column_values(fancy: &FancyDbObj, columns: &[ExportColumn]) -> Vec<(ExportColumn, Value)> {
+    let score = columns
+        .iter()
+        .any(|column| {
+            matches!(
+                column,
+                ExportColumn::AddressMixedCase
+                    | ExportColumn::AddressShortEtherscan
+                    | ExportColumn::Scores
+            )
+        })
+        .then(|| score_fancy(fancy.address.addr()));
+    columns
+        .iter()
+        .map(|column| {
+            let value = match column {
+                ExportColumn::Address => json!(fancy.address),
+                ExportColumn::AddressMixedCase => {
+                    json!(score.as_ref().map(|s| &s.address_mixed_case))
+                }
+                ExportColumn::AddressShortEtherscan => {
+                    json!(score.as_ref().map(|s| &s.address_short_etherscan))
+                }
+                ExportColumn::Salt => json!(fancy.salt),
+                ExportColumn::Factory => json!(fancy.factory),
+                ExportColumn::InitCodeHash => json!(fancy.init_code_hash),
+                ExportColumn::PublicKeyBase => json!(fancy.public_key_base),
+                ExportColumn::Create3Scheme => json!(fancy.create3_scheme),
+                ExportColumn::Caller => json!(fancy.caller),
+                ExportColumn::ChainId => json!(fancy.chain_id),
+                ExportColumn::Created => json!(fancy.created),
+                ExportColumn::Score => json!(fancy.score),
+                ExportColumn::Price => json!(fancy.price),
+                ExportColumn::Category => json!(fancy.category),
+                ExportColumn::Owner => json!(fancy.owner),
+                ExportColumn::Job => json!(fancy.job),
+                ExportColumn::Scores => score
+                    .as_ref()
+                    .map(|s| {
+                        s.scores
+                            .iter()
+                            .map(|(category, entry)| (category.clone(), json!(entry.difficulty)))
+                            .collect::<Map<String, Value>>()
+                    })
+                    .map(Value::Object)
+                    .unwrap_or_default(),
+            };
+            (*column, value)
+        })
+        .collect()
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(s),
+        other => csv_field(&other.to_string()),
+    }
+}
+
+pub fn export_row(fancy: &FancyDbObj, format: ExportFormat, columns: &[ExportColumn]) -> String {
+    let values = column_values(fancy, columns);
+    match format {
+        ExportFormat::Csv => {
+            let mut fields = Vec::new();
+            for (column, value) in &values {
+                if *column == ExportColumn::Scores {
+                    fields.extend(SCORED_CATEGORIES.iter().map(|category| {
+                        csv_value(value.get(category.to_string()).unwrap_or(&Value::Null))
+                    }));
+                } else {
+                    fields.push(csv_value(value));
+                }
+            }
+            fields.join(",") + "\n"
+        }
+        ExportFormat::Jsonl => {
+            let object = values
+                .into_iter()
+                .map(|(column, value)| (column.name(), value))
+                .collect::<Map<String, Value>>();
+            Value::Object(object).to_string() + "\n"
+        }
+    }
+}
+
+/// Next page of export as text, moves query cursor forward. None when everything was written
+pub async fn export_next_batch(
+    conn: &SqlitePool,
+    query: &mut FancyQuery,
+    format: ExportFormat,
+    columns: &[ExportColumn],
+) -> Result<Option<String>, AddressologyError> {
+    let batch = query_fancy_objs(conn, query, EXPORT_BATCH_SIZE).await?;
+    let Some(last) = batch.last() else {
+        return Ok(None);
+    };
+    query.cursor = Some(FancyCursor::after(last, query.sort_by));
+    Ok(Some(
+        batch
+            .iter()
+            .map(|fancy| export_row(fancy, format, columns))
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::create_sqlite_connection;
+    use crate::db::ops::{insert_fancy_obj, test_deployer, test_fancy_obj};
+    use crate::fancy::{parse_fancy_create3, parse_verify_input, recompute_fancy};
+    use crate::hash::Create3Scheme;
+
+    #[tokio::test]
+    async fn test_export() {
+        let conn = create_sqlite_connection(None, true).await.unwrap();
+        let count = EXPORT_BATCH_SIZE + 5;
+        for salt in 0..count {
+            let mut fancy = test_fancy_obj(salt as u64);
+            fancy.owner = (salt == 0).then(|| "Acme, \"Inc\"".to_string());
+            insert_fancy_obj(&conn, &fancy).await.unwrap();
+        }
+
+        let columns = parse_export_columns("address,owner,scores").unwrap();
+        let mut query = FancyQuery::default();
+        let mut csv = export_header(ExportFormat::Csv, &columns);
+        let mut batches = 0;
+        while let Some(batch) = export_next_batch(&conn, &mut query, ExportFormat::Csv, &columns)
+            .await
+            .unwrap()
+        {
+            csv.push_str(&batch);
+            batches += 1;
+        }
+        assert_eq!(batches, 2);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len() as i64, count + 1);
+        assert!(lines[0].starts_with("address,owner,scores.random,scores.leading_zeroes"));
+        assert_eq!(lines[0].split(',').count(), 2 + SCORED_CATEGORIES.len());
+        assert!(csv.contains(r#""Acme, ""Inc""""#));
+
+        let mut query = FancyQuery::default();
+        let jsonl = export_next_batch(&conn, &mut query, ExportFormat::Jsonl, &columns)
+            .await
+            .unwrap()
+            .unwrap();
+        let first: Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert!(first["address"].is_string());
+        assert!(first["scores"]["leading_zeroes"].is_number());
+
+        assert!(parse_export_columns("address,nope").is_err());
+        assert_eq!(
+            parse_export_columns("").unwrap(),
+            DEFAULT_EXPORT_COLUMNS.to_vec()
+        );
+
+        // default export verifies without knowing the target
+        let create3 = parse_fancy_create3(
+            format!("0x{:064x}", count),
+            test_deployer(),
+            Create3Scheme::ZeframLou,
+            Some(test_deployer()),
+            None,
+        )
+        .unwrap();
+        insert_fancy_obj(&conn, &create3).await.unwrap();
+        for format in [ExportFormat::Csv, ExportFormat::Jsonl] {
+            let mut query = FancyQuery::default();
+            let mut content = export_header(format, &DEFAULT_EXPORT_COLUMNS);
+            while let Some(batch) =
+                export_next_batch(&conn, &mut query, format, &DEFAULT_EXPORT_COLUMNS)
+                    .await
+                    .unwrap()
+            {
+                content.push_str(&batch);
+            }
+            let records = parse_verify_input(&content).unwrap();
+            assert_eq!(records.len() as i64, count + 1);
+            for (line, record) in records {
+                let record = record.unwrap();
+                let recomputed = recompute_fancy(&record).unwrap();
+                assert_eq!(recomputed.address, record.address, "line {line}");
+            }
+        }
+    }
+}
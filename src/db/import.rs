@@ -0,0 +1,381 @@
+use crate::db::ops::{insert_fancy_obj, update_fancy_created_if_earlier};
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use crate::fancy::{
+    csv_field, map_in_threads, parse_csv_record, parse_fancy, parse_fancy_create2,
+    parse_fancy_create3, parse_fancy_hook, parse_fancy_private, read_csv, read_jsonl,
+    recompute_fancy, FancyDbObj, FancyDbObjMin, VerifyMismatch,
+};
+use crate::runner::WorkTarget;
+use crate::types::DbAddress;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Raw format is a concatenation of 32 byte salts, as returned by results/consume/raw
+const RAW_SALT_LEN: usize = 32;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportFormat {
+    Jsonl,
+    Csv,
+    Raw,
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "jsonl" => Ok(ImportFormat::Jsonl),
+            "csv" => Ok(ImportFormat::Csv),
+            "raw" => Ok(ImportFormat::Raw),
+            _ => Err(format!("Unknown import format {s}, use jsonl, csv or raw")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportSource {
+    Record(FancyDbObjMin),
+    /// Salt without address, target is needed to compute it
+    Salt(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRecord {
+    /// Line in text input, position of salt in raw input
+    pub line: usize,
+    pub source: Result<ImportSource, String>,
+    pub created: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub total: usize,
+    pub verified: usize,
+    pub rejected: Vec<VerifyMismatch>,
+    /// Records with address already seen earlier in the same input
+    pub duplicates: usize,
+    pub inserted: usize,
+    /// Already stored addresses, which got earlier created date from input
+    pub updated_created: usize,
+    pub already_stored: usize,
+    pub categories: BTreeMap<String, usize>,
+}
+
+fn parse_created(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim().trim_matches('"');
+    NaiveDateTime::from_str(value)
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()
+}
+
+fn parse_raw_import(content: &[u8]) -> Result<Vec<ImportRecord>, AddressologyError> {
+    if !content.len().is_multiple_of(RAW_SALT_LEN) {
+        return Err(err_custom_create!(
+            "Raw input length {} is not a multiple of {RAW_SALT_LEN}",
+            content.len()
+        ));
+    }
+    Ok(content
+        .chunks(RAW_SALT_LEN)
+        .enumerate()
+        .map(|(idx, salt)| ImportRecord {
+            line: idx + 1,
+            source: Ok(ImportSource::Salt(format!("0x{}", hex::encode(salt)))),
+            created: None,
+        })
+        .collect())
+}
+
+// created date is read from the same record, FancyDbObjMin does not carry it
+fn parse_jsonl_import(content: &str) -> Vec<ImportRecord> {
+    read_jsonl(content)
+        .map(|(line, text)| match serde_json::from_str::<Value>(text) {
+            Ok(value) => ImportRecord {
+                line,
+                created: value
+                    .get("created")
+                    .and_then(Value::as_str)
+                    .and_then(parse_created),
+                source: serde_json::from_value::<FancyDbObjMin>(value)
+                    .map(ImportSource::Record)
+                    .map_err(|e| e.to_string()),
+            },
+            Err(err) => ImportRecord {
+                line,
+                source: Err(err.to_string()),
+                created: None,
+            },
+        })
+        .collect()
+}
+
+fn parse_csv_import(content: &str) -> Result<Vec<ImportRecord>, AddressologyError> {
+    let (header, records) = read_csv(content)?;
+    Ok(records
+        .into_iter()
+        .map(|(line, values)| ImportRecord {
+            line,
+            created: values
+                .as_ref()
+                .ok()
+                .and_then(|values| csv_field(&header, values, &["created"]))
+                .and_then(parse_created),
+            source: values.and_then(|values| {
+                parse_csv_record(&header, &values)
+                    .map(ImportSource::Record)
+                    .map_err(|e| e.to_string())
+            }),
+        })
+        .collect())
+}
+
+/// Format is guessed from content when not given, raw has to be requested explicitly
+pub fn parse_import_input(
+    content: &[u8],
+    format: Option<ImportFormat>,
+) -> Result<Vec<ImportRecord>, AddressologyError> {
+    let format = format.unwrap_or(if content.trim_ascii_start().starts_with(b"{") {
+        ImportFormat::Jsonl
+    } else {
+        ImportFormat::Csv
+    });
+    let text = || {
+        std::str::from_utf8(content)
+            .map_err(|e| err_custom_create!("Import input is not valid UTF-8: {e}"))
+    };
+    match format {
+        ImportFormat::Raw => parse_raw_import(content),
+        ImportFormat::Jsonl => Ok(parse_jsonl_import(text()?)),
+        ImportFormat::Csv => parse_csv_import(text()?),
+    }
+}
+
+/// Compute address from salt the same way runner verifies results for its target
+pub fn recompute_for_target(
+    salt: &str,
+    target: &WorkTarget,
+) -> Result<FancyDbObj, AddressologyError> {
+    match target {
+        WorkTarget::Factory(factory) => parse_fancy(salt.to_string(), factory.addr()),
+        WorkTarget::PublicKeyBase(public_key_base) => {
+            parse_fancy_private(public_key_base.clone(), salt.to_string())
+        }
+        WorkTarget::Create2 {
+            deployer,
+            init_code_hash,
+        } => parse_fancy_create2(salt.to_string(), deployer.addr(), init_code_hash.clone()),
+        WorkTarget::Create3 {
+            factory,
+            scheme,
+            caller,
+            chain_id,
+        } => parse_fancy_create3(
+            salt.to_string(),
+            factory.addr(),
+            *scheme,
+            caller.map(|caller| caller.addr()),
+            *chain_id,
+        ),
+        WorkTarget::UniswapHook { factory, flags } => {
+            parse_fancy_hook(salt.to_string(), factory.addr(), flags)
+        }
+        WorkTarget::Default => Err(err_custom_create!("Target is needed to import raw salts")),
+    }
+}
+
+fn verify_import_record(
+    record: &ImportRecord,
+    target: Option<&WorkTarget>,
+) -> Result<FancyDbObj, VerifyMismatch> {
+    let mismatch =
+        |address: Option<DbAddress>, computed: Option<DbAddress>, error: String| VerifyMismatch {
+            line: record.line,
+            address: address.map(|a| a.to_string()),
+            computed: computed.map(|a| a.to_string()),
+            error: (!error.is_empty()).then_some(error),
+        };
+    let source = record
+        .source
+        .as_ref()
+        .map_err(|err| mismatch(None, None, err.clone()))?;
+    let mut fancy = match (source, target) {
+        (ImportSource::Salt(salt), Some(target)) => {
+            recompute_for_target(salt, target).map_err(|e| mismatch(None, None, e.to_string()))?
+        }
+        (ImportSource::Salt(_), None) => {
+            return Err(mismatch(None, None, "Raw salt needs target".to_string()))
+        }
+        (ImportSource::Record(min), target) => {
+            let recomputed = match target {
+                Some(target) => recompute_for_target(&min.salt, target),
+                None => recompute_fancy(min),
+            }
+            .map_err(|e| mismatch(Some(min.address), None, e.to_string()))?;
+            if recomputed.address != min.address {
+                return Err(mismatch(
+                    Some(min.address),
+                    Some(recomputed.address),
+                    String::new(),
+                ));
+            }
+            recomputed
+        }
+    };
+    if let Some(created) = record.created {
+        fancy.created = created;
+    }
+    Ok(fancy)
+}
+
+/// Verify and re-score records, duplicates in input are merged keeping the earliest created date
+pub fn verify_import(
+    records: &[ImportRecord],
+    target: Option<&WorkTarget>,
+    threads: usize,
+) -> (Vec<FancyDbObj>, ImportReport) {
+    let results = map_in_threads(records, threads, |record| {
+        verify_import_record(record, target)
+    });
+
+    let mut report = ImportReport {
+        total: records.len(),
+        ..Default::default()
+    };
+    let mut unique: BTreeMap<DbAddress, FancyDbObj> = BTreeMap::new();
+    for result in results {
+        match result {
+            Ok(fancy) => {
+                report.verified += 1;
+                match unique.get_mut(&fancy.address) {
+                    Some(existing) => {
+                        report.duplicates += 1;
+                        existing.created = existing.created.min(fancy.created);
+                    }
+                    None => {
+                        unique.insert(fancy.address, fancy);
+                    }
+                }
+            }
+            Err(mismatch) => report.rejected.push(mismatch),
+        }
+    }
+    (unique.into_values().collect(), report)
+}
+
+/// Store verified records, for already stored addresses only the created date can move earlier
+pub async fn store_import(
+    conn: &SqlitePool,
+    fancies: &[FancyDbObj],
+    report: &mut ImportReport,
+) -> Result<(), AddressologyError> {
+    for fancy in fancies {
+        if insert_fancy_obj(conn, fancy).await? {
+            report.inserted += 1;
+            *report.categories.entry(fancy.category.clone()).or_default() += 1;
+        } else if update_fancy_created_if_earlier(conn, fancy.address, fancy.created).await? {
+            report.updated_created += 1;
+        } else {
+            report.already_stored += 1;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::create_sqlite_connection;
+    use crate::db::ops::{
+        get_fancy_obj, test_deployer, test_fancy_obj, TEST_DEPLOYER, TEST_INIT_CODE_HASH,
+    };
+
+    #[tokio::test]
+    async fn test_import() {
+        let conn = create_sqlite_connection(None, true).await.unwrap();
+        let fancies = (1..=3).map(test_fancy_obj).collect::<Vec<_>>();
+        let early = parse_created("2024-01-01T00:00:00").unwrap();
+        let late = parse_created("2025-01-01 00:00:00").unwrap();
+
+        // first address is already stored with later date
+        let mut stored = fancies[0].clone();
+        stored.created = late;
+        insert_fancy_obj(&conn, &stored).await.unwrap();
+
+        let json_line = |fancy: &FancyDbObj, created: NaiveDateTime| {
+            let mut value = serde_json::to_value(fancy).unwrap();
+            value["created"] = serde_json::json!(created);
+            value.to_string()
+        };
+        let jsonl = [
+            json_line(&fancies[0], early),
+            json_line(&fancies[1], late),
+            json_line(&fancies[1], early),
+            r#"{"address":"0x0000000000000000000000000000000000000001","salt":"0x0000000000000000000000000000000000000000000000000000000000000001","factory":"0x9e3f8eae49e442a323ef2094f277bf62752e6995","initCodeHash":"0x1e3a8b7e7c8bdbc3a3ab3dbbb0f4b1b9e3bfe7e1b0d68e0b2f8a0a27c8e2e9b4","publicKeyBase":null}"#.to_string(),
+            "not json".to_string(),
+        ]
+        .join("\n");
+        let records = parse_import_input(jsonl.as_bytes(), None).unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0].created, Some(early));
+
+        let (verified, mut report) = verify_import(&records, None, 2);
+        assert_eq!(report.total, 5);
+        assert_eq!(report.verified, 3);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.rejected.len(), 2);
+        assert_eq!(report.rejected[0].line, 4);
+        assert!(report.rejected[0].computed.is_some());
+        assert_eq!(verified.len(), 2);
+        store_import(&conn, &verified, &mut report).await.unwrap();
+        assert_eq!(report.inserted, 1);
+        assert_eq!(report.updated_created, 1);
+        for fancy in &fancies[0..2] {
+            let loaded = get_fancy_obj(&conn, fancy.address).await.unwrap().unwrap();
+            assert_eq!(loaded.created, early);
+        }
+
+        // quoted field with separator and doubled quote must not shift the created column
+        let csv = format!(
+            "address,salt,factory,initCodeHash,note,created\n{},{},{},{},\"Acme, \"\"Inc\"\"\",{}\n",
+            fancies[2].address,
+            fancies[2].salt,
+            TEST_DEPLOYER,
+            TEST_INIT_CODE_HASH,
+            "2023-05-05T10:00:00"
+        );
+        let records = parse_import_input(csv.as_bytes(), Some(ImportFormat::Csv)).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].created, parse_created("2023-05-05T10:00:00"));
+        let (verified, report) = verify_import(&records, None, 1);
+        assert_eq!(report.verified, 1);
+        assert_eq!(verified[0].address, fancies[2].address);
+        // broken header fails whole input instead of rejecting every record
+        assert!(parse_import_input(b"address,\"salt\n0x01,0x02\n", None).is_err());
+
+        // raw salts only make sense with target
+        let raw = fancies
+            .iter()
+            .flat_map(|f| hex::decode(f.salt.trim_start_matches("0x")).unwrap())
+            .collect::<Vec<u8>>();
+        assert!(parse_import_input(&raw[1..], Some(ImportFormat::Raw)).is_err());
+        let records = parse_import_input(&raw, Some(ImportFormat::Raw)).unwrap();
+        assert_eq!(verify_import(&records, None, 1).1.rejected.len(), 3);
+        let target = WorkTarget::Create2 {
+            deployer: DbAddress::wrap(test_deployer()),
+            init_code_hash: TEST_INIT_CODE_HASH.to_string(),
+        };
+        let (verified, mut report) = verify_import(&records, Some(&target), 1);
+        assert_eq!(report.verified, 3);
+        store_import(&conn, &verified, &mut report).await.unwrap();
+        assert_eq!(report.inserted, 1);
+        assert_eq!(report.already_stored, 2);
+    }
+}
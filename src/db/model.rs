@@ -0,0 +1,224 @@
+use crate::fancy::FancyDbObj;
+use crate::types::DbAddress;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum FancySortBy {
+    #[default]
+    Score,
+    Created,
+    Address,
+    Price,
+}
+
+impl FancySortBy {
+    pub fn column(&self) -> &'static str {
+        match self {
+            FancySortBy::Score => "score",
+            FancySortBy::Created => "created",
+            FancySortBy::Address => "address",
+            FancySortBy::Price => "price",
+        }
+    }
+}
+
+impl Display for FancySortBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.column())
+    }
+}
+
+impl FromStr for FancySortBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "score" => Ok(FancySortBy::Score),
+            "created" => Ok(FancySortBy::Created),
+            "address" => Ok(FancySortBy::Address),
+            "price" => Ok(FancySortBy::Price),
+            _ => Err(format!("Unknown sort field {s}")),
+        }
+    }
+}
+
+/// Sort value of the last record of previous page
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FancySortValue {
+    Score(f64),
+    Created(NaiveDateTime),
+    Price(i64),
+    /// Address is the sort value itself
+    Address,
+}
+
+const CURSOR_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// Keyset position after the last record of previous page. Sort value is kept in the cursor,
+/// so pagination continues even when that record is deleted meanwhile.
+/// Text form is value_address, only address when sorting by address
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FancyCursor {
+    pub sort_value: FancySortValue,
+    pub address: DbAddress,
+}
+
+impl FancyCursor {
+    pub fn after(fancy: &FancyDbObj, sort_by: FancySortBy) -> Self {
+        let sort_value = match sort_by {
+            FancySortBy::Score => FancySortValue::Score(fancy.score),
+            FancySortBy::Created => FancySortValue::Created(fancy.created),
+            FancySortBy::Price => FancySortValue::Price(fancy.price),
+            FancySortBy::Address => FancySortValue::Address,
+        };
+        Self {
+            sort_value,
+            address: fancy.address,
+        }
+    }
+
+    pub fn parse(cursor: &str, sort_by: FancySortBy) -> Result<Self, String> {
+        let invalid = || format!("Invalid cursor {cursor} for sort by {sort_by}");
+        let (value, address) = match sort_by {
+            FancySortBy::Address => ("", cursor),
+            _ => cursor.rsplit_once('_').ok_or_else(invalid)?,
+        };
+        let address = DbAddress::from_str(address).map_err(|_| invalid())?;
+        let sort_value = match sort_by {
+            FancySortBy::Score => FancySortValue::Score(value.parse().map_err(|_| invalid())?),
+            FancySortBy::Created => FancySortValue::Created(
+                NaiveDateTime::parse_from_str(value, CURSOR_DATE_FORMAT).map_err(|_| invalid())?,
+            ),
+            FancySortBy::Price => FancySortValue::Price(value.parse().map_err(|_| invalid())?),
+            FancySortBy::Address => FancySortValue::Address,
+        };
+        Ok(Self {
+            sort_value,
+            address,
+        })
+    }
+}
+
+impl Display for FancyCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.sort_value {
+            // shortest representation that parses back to the same float
+            FancySortValue::Score(score) => write!(f, "{score}_{}", self.address),
+            FancySortValue::Created(created) => {
+                write!(f, "{}_{}", created.format(CURSOR_DATE_FORMAT), self.address)
+            }
+            FancySortValue::Price(price) => write!(f, "{price}_{}", self.address),
+            FancySortValue::Address => write!(f, "{}", self.address),
+        }
+    }
+}
+
+/// Filters over stored addresses, all given conditions have to match
+#[derive(Debug, Clone, Default)]
+pub struct FancyQuery {
+    pub category: Option<String>,
+    pub min_score: Option<f64>,
+    pub max_score: Option<f64>,
+    pub factory: Option<DbAddress>,
+    pub public_key_base: Option<String>,
+    pub owner: Option<String>,
+    pub job: Option<String>,
+    pub created_from: Option<NaiveDateTime>,
+    pub created_to: Option<NaiveDateTime>,
+    /// Regex on lowercase address with 0x prefix
+    pub address_regex: Option<String>,
+    pub sort_by: FancySortBy,
+    pub ascending: bool,
+    pub cursor: Option<FancyCursor>,
+}
+
+#[derive(Serialize, Deserialize, sqlx::Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(type_name = "TEXT", rename_all = "camelCase")]
+pub enum OwnershipAction {
+    Reserve,
+    Assign,
+    Release,
+    Transfer,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OwnershipStatus {
+    Available,
+    Reserved,
+    Owned,
+}
+
+#[derive(Serialize, Deserialize, sqlx::FromRow, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipState {
+    pub address: DbAddress,
+    pub owner: Option<String>,
+    pub reserved_by: Option<String>,
+    pub reserved_until: Option<NaiveDateTime>,
+}
+
+impl OwnershipState {
+    /// Reservation past its expiry does not block anything, it only stays until overwritten
+    pub fn status(&self, now: NaiveDateTime) -> OwnershipStatus {
+        if self.owner.is_some() {
+            OwnershipStatus::Owned
+        } else if self.reserved_by.is_some() && self.reserved_until.is_some_and(|until| until > now)
+        {
+            OwnershipStatus::Reserved
+        } else {
+            OwnershipStatus::Available
+        }
+    }
+}
+
+/// Audit trail entry, state of the address after the change
+#[derive(Serialize, Deserialize, sqlx::FromRow, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipEvent {
+    pub id: i64,
+    pub address: DbAddress,
+    pub action: OwnershipAction,
+    pub actor: String,
+    pub previous_owner: Option<String>,
+    pub owner: Option<String>,
+    pub reserved_by: Option<String>,
+    pub reserved_until: Option<NaiveDateTime>,
+    pub created: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum OwnershipChange {
+    /// Reserving again by the same prospect extends the reservation
+    #[serde(rename_all = "camelCase")]
+    Reserve {
+        reserved_by: String,
+        expires_in_secs: i64,
+    },
+    /// Only available address or address reserved for the same owner can be assigned
+    Assign {
+        owner: String,
+    },
+    Release,
+    Transfer {
+        from: String,
+        to: String,
+    },
+}
+
+impl OwnershipChange {
+    pub fn action(&self) -> OwnershipAction {
+        match self {
+            OwnershipChange::Reserve { .. } => OwnershipAction::Reserve,
+            OwnershipChange::Assign { .. } => OwnershipAction::Assign,
+            OwnershipChange::Release => OwnershipAction::Release,
+            OwnershipChange::Transfer { .. } => OwnershipAction::Transfer,
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use crate::db::model::{FancyQuery, FancySortValue};
+use crate::err_from;
+use crate::error::{AddressologyError, ErrorBag};
+use crate::fancy::FancyDbObj;
+use crate::types::DbAddress;
+use chrono::NaiveDateTime;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+
+/// Insert found address, returns false when address is already stored
+pub async fn insert_fancy_obj(
+    conn: &SqlitePool,
+    fancy: &FancyDbObj,
+) -> Result<bool, AddressologyError> {
+    let res = sqlx::query(
+        r"INSERT OR IGNORE INTO fancy
+        (address, salt, factory, init_code_hash, public_key_base, created, score, owner, price, category, job,
+         create3_scheme, caller, chain_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
+    )
+    .bind(fancy.address)
+    .bind(&fancy.salt)
+    .bind(fancy.factory)
+    .bind(&fancy.init_code_hash)
+    .bind(&fancy.public_key_base)
+    .bind(fancy.created)
+    .bind(fancy.score)
+    .bind(&fancy.owner)
+    .bind(fancy.price)
+    .bind(&fancy.category)
+    .bind(&fancy.job)
+    .bind(&fancy.create3_scheme)
+    .bind(fancy.caller)
+    .bind(fancy.chain_id)
+    .execute(conn)
+    .await
+    .map_err(err_from!())?;
+    Ok(res.rows_affected() == 1)
+}
+
+#[cfg(test)]
+pub const TEST_DEPLOYER: &str = "0x9E3F8eaE49E442A323EF2094f277Bf62752E6995";
+#[cfg(test)]
+pub const TEST_INIT_CODE_HASH: &str =
+    "0x1e3a8b7e7c8bdbc3a3ab3dbbb0f4b1b9e3bfe7e1b0d68e0b2f8a0a27c8e2e9b4";
+
+#[cfg(test)]
+pub fn test_deployer() -> web3::types::Address {
+    TEST_DEPLOYER.parse().unwrap()
+}
+
+/// CREATE2 record of test deployer with salt number, shared by database tests
+#[cfg(test)]
+pub fn test_fancy_obj(salt: u64) -> FancyDbObj {
+    crate::fancy::parse_fancy_create2(
+        format!("0x{salt:064x}"),
+        test_deployer(),
+        TEST_INIT_CODE_HASH.to_string(),
+    )
+    .unwrap()
+}
+
+/// Set created to the earlier date, returns false when stored date is not later
+pub async fn update_fancy_created_if_earlier(
+    conn: &SqlitePool,
+    address: DbAddress,
+    created: NaiveDateTime,
+) -> Result<bool, AddressologyError> {
+    let res = sqlx::query(r"UPDATE fancy SET created = $1 WHERE address = $2 AND created > $1")
+        .bind(created)
+        .bind(address)
+        .execute(conn)
+        .await
+        .map_err(err_from!())?;
+    Ok(res.rows_affected() == 1)
+}
+
+pub async fn get_fancy_obj(
+    conn: &SqlitePool,
+    address: DbAddress,
+) -> Result<Option<FancyDbObj>, AddressologyError> {
+    sqlx::query_as::<_, FancyDbObj>(r"SELECT * FROM fancy WHERE address = $1")
+        .bind(address)
+        .fetch_optional(conn)
+        .await
+        .map_err(err_from!())
+}
+
+/// Best addresses first, optionally only from one category
+pub async fn list_fancy_objs(
+    conn: &SqlitePool,
+    category: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<FancyDbObj>, AddressologyError> {
+    sqlx::query_as::<_, FancyDbObj>(
+        r"SELECT * FROM fancy
+        WHERE $1 IS NULL OR category = $1
+        ORDER BY score DESC, address
+        LIMIT $2 OFFSET $3",
+    )
+    .bind(category)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(conn)
+    .await
+    .map_err(err_from!())
+}
+
+fn push_fancy_filters<'a>(builder: &mut QueryBuilder<'a, Sqlite>, query: &'a FancyQuery) {
+    builder.push(" WHERE 1 = 1");
+    if let Some(category) = &query.category {
+        builder.push(" AND category = ").push_bind(category);
+    }
+    if let Some(min_score) = query.min_score {
+        builder.push(" AND score >= ").push_bind(min_score);
+    }
+    if let Some(max_score) = query.max_score {
+        builder.push(" AND score <= ").push_bind(max_score);
+    }
+    if let Some(factory) = query.factory {
+        builder.push(" AND factory = ").push_bind(factory);
+    }
+    if let Some(public_key_base) = &query.public_key_base {
+        builder
+            .push(" AND public_key_base = ")
+            .push_bind(public_key_base);
+    }
+    if let Some(owner) = &query.owner {
+        builder.push(" AND owner = ").push_bind(owner);
+    }
+    if let Some(job) = &query.job {
+        builder.push(" AND job = ").push_bind(job);
+    }
+    if let Some(created_from) = query.created_from {
+        builder.push(" AND created >= ").push_bind(created_from);
+    }
+    if let Some(created_to) = query.created_to {
+        builder.push(" AND created <= ").push_bind(created_to);
+    }
+    if let Some(address_regex) = &query.address_regex {
+        builder
+            .push(" AND address REGEXP ")
+            .push_bind(address_regex);
+    }
+}
+
+/// Page of addresses matching query, keyset pagination continues after query.cursor
+pub async fn query_fancy_objs(
+    conn: &SqlitePool,
+    query: &FancyQuery,
+    limit: i64,
+) -> Result<Vec<FancyDbObj>, AddressologyError> {
+    let column = query.sort_by.column();
+    let (compare, order) = if query.ascending {
+        (">", "ASC")
+    } else {
+        ("<", "DESC")
+    };
+    let mut builder = QueryBuilder::new("SELECT * FROM fancy");
+    push_fancy_filters(&mut builder, query);
+    if let Some(cursor) = query.cursor {
+        // address is unique, so (sort column, address) is a strict order
+        builder.push(format!(" AND ({column}, address) {compare} ("));
+        match cursor.sort_value {
+            FancySortValue::Score(score) => builder.push_bind(score),
+            FancySortValue::Created(created) => builder.push_bind(created),
+            FancySortValue::Price(price) => builder.push_bind(price),
+            FancySortValue::Address => builder.push_bind(cursor.address),
+        };
+        builder.push(", ").push_bind(cursor.address).push(")");
+    }
+    builder
+        .push(format!(
+            " ORDER BY {column} {order}, address {order} LIMIT "
+        ))
+        .push_bind(limit);
+    builder
+        .build_query_as::<FancyDbObj>()
+        .fetch_all(conn)
+        .await
+        .map_err(err_from!())
+}
+
+pub async fn count_fancy_objs(
+    conn: &SqlitePool,
+    query: &FancyQuery,
+) -> Result<i64, AddressologyError> {
+    let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM fancy");
+    push_fancy_filters(&mut builder, query);
+    builder
+        .build_query_scalar::<i64>()
+        .fetch_one(conn)
+        .await
+        .map_err(err_from!())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::create_sqlite_connection;
+    use crate::db::model::{FancyCursor, FancySortBy};
+
+    #[tokio::test]
+    async fn test_fancy_ops() {
+        let conn = create_sqlite_connection(None, true).await.unwrap();
+        let mut stored = Vec::new();
+        for salt in 1..=3 {
+            let fancy = test_fancy_obj(salt);
+            assert!(insert_fancy_obj(&conn, &fancy).await.unwrap());
+            stored.push(fancy);
+        }
+        assert!(!insert_fancy_obj(&conn, &stored[0]).await.unwrap());
+
+        let loaded = get_fancy_obj(&conn, stored[1].address).await.unwrap();
+        assert_eq!(loaded.as_ref(), Some(&stored[1]));
+
+        let all = list_fancy_objs(&conn, None, 10, 0).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all.windows(2).all(|w| w[0].score >= w[1].score));
+        assert_eq!(list_fancy_objs(&conn, None, 2, 2).await.unwrap().len(), 1);
+        let category = &stored[0].category;
+        let in_category = list_fancy_objs(&conn, Some(category), 10, 0).await.unwrap();
+        assert!(!in_category.is_empty());
+        assert!(in_category.iter().all(|f| &f.category == category));
+
+        // create3 parameters are stored, so the record can be recomputed later
+        let create3 = crate::fancy::parse_fancy_create3(
+            format!("0x{:064x}", 4),
+            test_deployer(),
+            crate::hash::Create3Scheme::ZeframLou,
+            Some(test_deployer()),
+            Some(8453),
+        )
+        .unwrap();
+        assert!(insert_fancy_obj(&conn, &create3).await.unwrap());
+        let loaded = get_fancy_obj(&conn, create3.address).await.unwrap();
+        assert_eq!(loaded.as_ref(), Some(&create3));
+    }
+
+    #[tokio::test]
+    async fn test_query_fancy_objs() {
+        let conn = create_sqlite_connection(None, true).await.unwrap();
+        let created = chrono::NaiveDate::from_ymd_opt(2025, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        for salt in 1..=10 {
+            let mut fancy = test_fancy_obj(salt as u64);
+            fancy.score = salt as f64;
+            fancy.created = created + chrono::Duration::days(salt);
+            fancy.owner = (salt % 2 == 0).then(|| "even".to_string());
+            insert_fancy_obj(&conn, &fancy).await.unwrap();
+        }
+
+        let query = FancyQuery {
+            min_score: Some(3.0),
+            max_score: Some(8.0),
+            ..Default::default()
+        };
+        assert_eq!(count_fancy_objs(&conn, &query).await.unwrap(), 6);
+        let mut pages = Vec::new();
+        let mut query = query;
+        loop {
+            let page = query_fancy_objs(&conn, &query, 4).await.unwrap();
+            if page.is_empty() {
+                break;
+            }
+            query.cursor = page.last().map(|f| FancyCursor::after(f, query.sort_by));
+            pages.push(page.iter().map(|f| f.score).collect::<Vec<_>>());
+        }
+        assert_eq!(pages, vec![vec![8.0, 7.0, 6.0, 5.0], vec![4.0, 3.0]]);
+
+        // last record of the page is gone before next page is read
+        for sort_by in [
+            FancySortBy::Score,
+            FancySortBy::Created,
+            FancySortBy::Price,
+            FancySortBy::Address,
+        ] {
+            let query = FancyQuery {
+                sort_by,
+                ascending: true,
+                ..Default::default()
+            };
+            let page = query_fancy_objs(&conn, &query, 3).await.unwrap();
+            let cursor = FancyCursor::after(&page[2], sort_by);
+            assert_eq!(FancyCursor::parse(&cursor.to_string(), sort_by), Ok(cursor));
+            sqlx::query("DELETE FROM fancy WHERE address = $1")
+                .bind(page[2].address)
+                .execute(&conn)
+                .await
+                .unwrap();
+            let query = FancyQuery {
+                cursor: Some(cursor),
+                ..query
+            };
+            let next = query_fancy_objs(&conn, &query, 3).await.unwrap();
+            assert_eq!(next.len(), 3);
+            assert!(next.iter().all(|f| !page.contains(f)));
+            insert_fancy_obj(&conn, &page[2]).await.unwrap();
+        }
+        assert!(FancyCursor::parse("0x01", FancySortBy::Score).is_err());
+
+        let query = FancyQuery {
+            owner: Some("even".to_string()),
+            created_from: Some(created + chrono::Duration::days(4)),
+            sort_by: FancySortBy::Created,
+            ascending: true,
+            ..Default::default()
+        };
+        let scores = query_fancy_objs(&conn, &query, 10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|f| f.score)
+            .collect::<Vec<_>>();
+        assert_eq!(scores, vec![4.0, 6.0, 8.0, 10.0]);
+
+        let all = query_fancy_objs(&conn, &FancyQuery::default(), 10)
+            .await
+            .unwrap();
+        let prefix = &all[0].address.to_string()[..6];
+        let query = FancyQuery {
+            address_regex: Some(format!("^{prefix}")),
+            ..Default::default()
+        };
+        let matching = query_fancy_objs(&conn, &query, 10).await.unwrap();
+        assert!(!matching.is_empty());
+        assert!(matching
+            .iter()
+            .all(|f| f.address.to_string().starts_with(prefix)));
+    }
+}
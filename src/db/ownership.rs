@@ -0,0 +1,286 @@
+use crate::config::get_max_reservation_secs;
+use crate::db::model::{OwnershipChange, OwnershipEvent, OwnershipState, OwnershipStatus};
+use crate::error::{AddressologyError, ErrorBag};
+use crate::types::DbAddress;
+use crate::{err_custom_create, err_from};
+use chrono::NaiveDateTime;
+use sqlx::{Executor, Sqlite, SqlitePool};
+
+async fn fetch_ownership_state<'c, E: Executor<'c, Database = Sqlite>>(
+    executor: E,
+    address: DbAddress,
+) -> Result<Option<OwnershipState>, AddressologyError> {
+    sqlx::query_as::<_, OwnershipState>(
+        r"SELECT address, owner, reserved_by, reserved_until FROM fancy WHERE address = $1",
+    )
+    .bind(address)
+    .fetch_optional(executor)
+    .await
+    .map_err(err_from!())
+}
+
+pub async fn get_ownership_state(
+    conn: &SqlitePool,
+    address: DbAddress,
+) -> Result<Option<OwnershipState>, AddressologyError> {
+    fetch_ownership_state(conn, address).await
+}
+
+/// State after the change, or error when the change is not allowed in current state
+pub fn apply_ownership_change(
+    state: &OwnershipState,
+    change: &OwnershipChange,
+    now: NaiveDateTime,
+) -> Result<OwnershipState, AddressologyError> {
+    let status = state.status(now);
+    let address = state.address;
+    match change {
+        OwnershipChange::Reserve {
+            reserved_by,
+            expires_in_secs,
+        } => {
+            let max_secs = get_max_reservation_secs();
+            if !(1..=max_secs).contains(expires_in_secs) {
+                return Err(err_custom_create!(
+                    "Reservation has to expire in 1 to {max_secs} seconds, got {expires_in_secs}"
+                ));
+            }
+            match status {
+                OwnershipStatus::Owned => {
+                    return Err(err_custom_create!("Address {address} is already owned"))
+                }
+                OwnershipStatus::Reserved if state.reserved_by.as_ref() != Some(reserved_by) => {
+                    return Err(err_custom_create!(
+                        "Address {address} is reserved for someone else"
+                    ))
+                }
+                _ => {}
+            }
+            Ok(OwnershipState {
+                address,
+                owner: None,
+                reserved_by: Some(reserved_by.clone()),
+                reserved_until: Some(now + chrono::Duration::seconds(*expires_in_secs)),
+            })
+        }
+        OwnershipChange::Assign { owner } => match status {
+            OwnershipStatus::Owned => Err(err_custom_create!("Address {address} is already owned")),
+            OwnershipStatus::Reserved if state.reserved_by.as_ref() != Some(owner) => Err(
+                err_custom_create!("Address {address} is reserved for someone else"),
+            ),
+            _ => Ok(OwnershipState {
+                address,
+                owner: Some(owner.clone()),
+                reserved_by: None,
+                reserved_until: None,
+            }),
+        },
+        OwnershipChange::Release => match status {
+            OwnershipStatus::Reserved => Ok(OwnershipState {
+                address,
+                owner: None,
+                reserved_by: None,
+                reserved_until: None,
+            }),
+            _ => Err(err_custom_create!("Address {address} is not reserved")),
+        },
+        OwnershipChange::Transfer { from, to } => {
+            if state.owner.as_ref() != Some(from) {
+                return Err(err_custom_create!(
+                    "Address {address} is not owned by {from}"
+                ));
+            }
+            if from == to {
+                return Err(err_custom_create!(
+                    "Cannot transfer address {address} to its owner"
+                ));
+            }
+            Ok(OwnershipState {
+                owner: Some(to.clone()),
+                ..state.clone()
+            })
+        }
+    }
+}
+
+/// Apply change and record it in audit trail. Update is conditional on the state it was checked
+/// against, so concurrent changes cannot sell the same address twice
+pub async fn change_ownership(
+    conn: &SqlitePool,
+    address: DbAddress,
+    change: &OwnershipChange,
+    actor: &str,
+) -> Result<OwnershipEvent, AddressologyError> {
+    if actor.trim().is_empty() {
+        return Err(err_custom_create!(
+            "Actor is required for ownership changes"
+        ));
+    }
+    let now = chrono::Utc::now().naive_utc();
+    let mut tx = conn.begin().await.map_err(err_from!())?;
+    let state = fetch_ownership_state(&mut *tx, address)
+        .await?
+        .ok_or_else(|| err_custom_create!("Address {address} is not stored"))?;
+    let next = apply_ownership_change(&state, change, now)?;
+    let res = sqlx::query(
+        r"UPDATE fancy SET owner = $1, reserved_by = $2, reserved_until = $3
+        WHERE address = $4 AND owner IS $5 AND reserved_by IS $6 AND reserved_until IS $7",
+    )
+    .bind(&next.owner)
+    .bind(&next.reserved_by)
+    .bind(next.reserved_until)
+    .bind(address)
+    .bind(&state.owner)
+    .bind(&state.reserved_by)
+    .bind(state.reserved_until)
+    .execute(&mut *tx)
+    .await
+    .map_err(err_from!())?;
+    if res.rows_affected() != 1 {
+        return Err(err_custom_create!(
+            "Address {address} was changed concurrently, try again"
+        ));
+    }
+    let event = sqlx::query_as::<_, OwnershipEvent>(
+        r"INSERT INTO fancy_ownership_event
+        (address, action, actor, previous_owner, owner, reserved_by, reserved_until, created)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *",
+    )
+    .bind(address)
+    .bind(change.action())
+    .bind(actor)
+    .bind(&state.owner)
+    .bind(&next.owner)
+    .bind(&next.reserved_by)
+    .bind(next.reserved_until)
+    .bind(now)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(err_from!())?;
+    tx.commit().await.map_err(err_from!())?;
+    Ok(event)
+}
+
+/// Audit trail of address, oldest first
+pub async fn list_ownership_events(
+    conn: &SqlitePool,
+    address: DbAddress,
+) -> Result<Vec<OwnershipEvent>, AddressologyError> {
+    sqlx::query_as::<_, OwnershipEvent>(
+        r"SELECT * FROM fancy_ownership_event WHERE address = $1 ORDER BY id",
+    )
+    .bind(address)
+    .fetch_all(conn)
+    .await
+    .map_err(err_from!())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::create_sqlite_connection;
+    use crate::db::model::OwnershipAction;
+    use crate::db::ops::{get_fancy_obj, insert_fancy_obj, test_deployer, test_fancy_obj};
+
+    #[tokio::test]
+    async fn test_ownership_workflow() {
+        let conn = create_sqlite_connection(None, true).await.unwrap();
+        let fancy = test_fancy_obj(1);
+        insert_fancy_obj(&conn, &fancy).await.unwrap();
+        let address = fancy.address;
+        let reserve = |by: &str| OwnershipChange::Reserve {
+            reserved_by: by.to_string(),
+            expires_in_secs: 3600,
+        };
+
+        let event = change_ownership(&conn, address, &reserve("alice"), "sales")
+            .await
+            .unwrap();
+        assert_eq!(event.action, OwnershipAction::Reserve);
+        assert_eq!(event.reserved_by.as_deref(), Some("alice"));
+        // extending own reservation is fine, taking someone else's is not
+        change_ownership(&conn, address, &reserve("alice"), "sales")
+            .await
+            .unwrap();
+        assert!(change_ownership(&conn, address, &reserve("bob"), "sales")
+            .await
+            .is_err());
+        let assign_bob = OwnershipChange::Assign {
+            owner: "bob".to_string(),
+        };
+        assert!(change_ownership(&conn, address, &assign_bob, "sales")
+            .await
+            .is_err());
+
+        // expired reservation does not block
+        sqlx::query("UPDATE fancy SET reserved_until = '2000-01-01 00:00:00' WHERE address = $1")
+            .bind(address)
+            .execute(&conn)
+            .await
+            .unwrap();
+        assert!(
+            change_ownership(&conn, address, &OwnershipChange::Release, "sales")
+                .await
+                .is_err()
+        );
+        change_ownership(&conn, address, &assign_bob, "payments")
+            .await
+            .unwrap();
+        assert_eq!(
+            get_fancy_obj(&conn, address)
+                .await
+                .unwrap()
+                .unwrap()
+                .owner
+                .as_deref(),
+            Some("bob")
+        );
+        assert!(change_ownership(&conn, address, &reserve("alice"), "sales")
+            .await
+            .is_err());
+
+        let transfer = |from: &str, to: &str| OwnershipChange::Transfer {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        assert!(
+            change_ownership(&conn, address, &transfer("alice", "carol"), "support")
+                .await
+                .is_err()
+        );
+        let event = change_ownership(&conn, address, &transfer("bob", "carol"), "support")
+            .await
+            .unwrap();
+        assert_eq!(event.previous_owner.as_deref(), Some("bob"));
+        assert_eq!(event.owner.as_deref(), Some("carol"));
+        assert!(change_ownership(&conn, address, &assign_bob, "")
+            .await
+            .is_err());
+
+        let events = list_ownership_events(&conn, address).await.unwrap();
+        let actions = events.iter().map(|e| e.action).collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![
+                OwnershipAction::Reserve,
+                OwnershipAction::Reserve,
+                OwnershipAction::Assign,
+                OwnershipAction::Transfer
+            ]
+        );
+        assert_eq!(events[2].actor, "payments");
+        let state = get_ownership_state(&conn, address).await.unwrap().unwrap();
+        assert_eq!(
+            state.status(chrono::Utc::now().naive_utc()),
+            OwnershipStatus::Owned
+        );
+        assert!(change_ownership(
+            &conn,
+            DbAddress::wrap(test_deployer()),
+            &assign_bob,
+            "sales"
+        )
+        .await
+        .is_err());
+    }
+}
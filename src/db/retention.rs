@@ -0,0 +1,432 @@
+use crate::error::{AddressologyError, ErrorBag};
+use crate::fancy::{score_fast, SCORED_CATEGORIES};
+use crate::types::DbAddress;
+use crate::{err_custom_create, err_from};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use web3::signing::keccak256;
+
+/// Candidates are read and pruned in pages of this size
+const RETENTION_BATCH_SIZE: i64 = 1000;
+
+/// Columns copied from fancy to fancy_archive, archive adds archived date
+const ARCHIVE_COLUMNS: &str = "address, salt, factory, init_code_hash, public_key_base, created, \
+    score, owner, price, category, job, reserved_by, reserved_until, create3_scheme, caller, chain_id";
+
+/// Retention section of config.toml, scores are difficulties like the stored score
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RetentionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Records with total score at least this are always kept
+    #[serde(default)]
+    pub keep_min_score: Option<f64>,
+    /// Records with score in given category (like short_leading_any) at least this are kept,
+    /// even if it is not their best category
+    #[serde(default)]
+    pub keep_categories: BTreeMap<String, f64>,
+    /// Percent of remaining records that is kept, picked by address hash so runs agree
+    #[serde(default)]
+    pub sample_percent: f64,
+    /// Only records older than this are pruned
+    #[serde(default = "default_max_age_days")]
+    pub max_age_days: i64,
+    /// Move pruned records to fancy_archive instead of deleting them
+    #[serde(default = "default_archive")]
+    pub archive: bool,
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_max_age_days() -> i64 {
+    30
+}
+
+fn default_archive() -> bool {
+    true
+}
+
+fn default_interval_secs() -> u64 {
+    3600
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            keep_min_score: None,
+            keep_categories: BTreeMap::new(),
+            sample_percent: 0.0,
+            max_age_days: default_max_age_days(),
+            archive: default_archive(),
+            interval_secs: default_interval_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    pub config: RetentionConfig,
+    /// Index into SCORED_CATEGORIES and minimum difficulty
+    category_thresholds: Vec<(usize, f64)>,
+}
+
+impl RetentionPolicy {
+    pub fn from_config(config: &RetentionConfig) -> Result<Self, AddressologyError> {
+        if !(0.0..=100.0).contains(&config.sample_percent) {
+            return Err(err_custom_create!(
+                "Sample percent has to be between 0 and 100, got {}",
+                config.sample_percent
+            ));
+        }
+        // without keep rules everything old enough would be pruned
+        if config.keep_min_score.is_none() && config.keep_categories.is_empty() {
+            return Err(err_custom_create!(
+                "Retention needs keep-min-score or keep-categories"
+            ));
+        }
+        if config.max_age_days < 0 {
+            return Err(err_custom_create!(
+                "Max age cannot be negative, got {}",
+                config.max_age_days
+            ));
+        }
+        let category_thresholds = config
+            .keep_categories
+            .iter()
+            .map(|(category, min_score)| {
+                SCORED_CATEGORIES
+                    .iter()
+                    .position(|c| &c.to_string() == category)
+                    .map(|idx| (idx, *min_score))
+                    .ok_or_else(|| err_custom_create!("Unknown retention category {category}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            config: config.clone(),
+            category_thresholds,
+        })
+    }
+
+    /// Same address always lands in the same sample
+    fn sampled(&self, address: &DbAddress) -> bool {
+        let hash = keccak256(address.addr().as_bytes());
+        let bucket = u16::from_be_bytes([hash[0], hash[1]]) as f64 / 65536.0 * 100.0;
+        bucket < self.config.sample_percent
+    }
+
+    pub fn keep(&self, address: &DbAddress, score: f64) -> bool {
+        if self
+            .config
+            .keep_min_score
+            .is_some_and(|min_score| score >= min_score)
+        {
+            return true;
+        }
+        if !self.category_thresholds.is_empty() {
+            let fast = score_fast(&address.addr());
+            if self
+                .category_thresholds
+                .iter()
+                .any(|(idx, min_score)| fast.entries[*idx].difficulty >= *min_score)
+            {
+                return true;
+            }
+        }
+        self.sampled(address)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionRunStats {
+    pub started: NaiveDateTime,
+    pub finished: NaiveDateTime,
+    /// Old records that are neither owned nor reserved
+    pub scanned: usize,
+    pub kept: usize,
+    pub deleted: usize,
+    pub archived: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionStats {
+    pub runs: u64,
+    pub total_deleted: u64,
+    pub total_archived: u64,
+    pub last_run: Option<RetentionRunStats>,
+    pub last_error: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct RetentionCandidate {
+    address: DbAddress,
+    score: f64,
+}
+
+/// Records may be assigned or reserved after they were scanned, so it is checked again
+fn push_prunable_addresses(
+    builder: &mut QueryBuilder<Sqlite>,
+    addresses: &[DbAddress],
+    now: NaiveDateTime,
+) {
+    builder
+        .push(" WHERE owner IS NULL AND (reserved_until IS NULL OR reserved_until <= ")
+        .push_bind(now)
+        .push(") AND address IN (");
+    let mut separated = builder.separated(", ");
+    for address in addresses {
+        separated.push_bind(*address);
+    }
+    separated.push_unseparated(")");
+}
+
+async fn prune_addresses(
+    conn: &SqlitePool,
+    addresses: &[DbAddress],
+    archive: bool,
+    now: NaiveDateTime,
+) -> Result<usize, AddressologyError> {
+    if addresses.is_empty() {
+        return Ok(0);
+    }
+    let mut tx = conn.begin().await.map_err(err_from!())?;
+    if archive {
+        let mut builder = QueryBuilder::new(format!(
+            "INSERT OR REPLACE INTO fancy_archive ({ARCHIVE_COLUMNS}, archived) SELECT {ARCHIVE_COLUMNS}, "
+        ));
+        builder.push_bind(now).push(" FROM fancy");
+        push_prunable_addresses(&mut builder, addresses, now);
+        builder
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(err_from!())?;
+    }
+    let mut builder = QueryBuilder::new("DELETE FROM fancy");
+    push_prunable_addresses(&mut builder, addresses, now);
+    let res = builder
+        .build()
+        .execute(&mut *tx)
+        .await
+        .map_err(err_from!())?;
+    tx.commit().await.map_err(err_from!())?;
+    Ok(res.rows_affected() as usize)
+}
+
+/// One pass over records older than max age, owned and actively reserved records are never pruned
+pub async fn run_retention(
+    conn: &SqlitePool,
+    policy: &RetentionPolicy,
+) -> Result<RetentionRunStats, AddressologyError> {
+    let started = chrono::Utc::now().naive_utc();
+    let cutoff = started - chrono::Duration::days(policy.config.max_age_days);
+    let mut stats = RetentionRunStats {
+        started,
+        ..Default::default()
+    };
+    let mut cursor: Option<DbAddress> = None;
+    loop {
+        let mut builder = QueryBuilder::new(
+            "SELECT address, score FROM fancy WHERE owner IS NULL \
+            AND (reserved_until IS NULL OR reserved_until <= ",
+        );
+        builder
+            .push_bind(started)
+            .push(") AND created < ")
+            .push_bind(cutoff);
+        if let Some(cursor) = cursor {
+            builder.push(" AND address > ").push_bind(cursor);
+        }
+        builder
+            .push(" ORDER BY address LIMIT ")
+            .push_bind(RETENTION_BATCH_SIZE);
+        let candidates = builder
+            .build_query_as::<RetentionCandidate>()
+            .fetch_all(conn)
+            .await
+            .map_err(err_from!())?;
+        let Some(last) = candidates.last() else {
+            break;
+        };
+        cursor = Some(last.address);
+        stats.scanned += candidates.len();
+        let to_prune = candidates
+            .iter()
+            .filter(|candidate| !policy.keep(&candidate.address, candidate.score))
+            .map(|candidate| candidate.address)
+            .collect::<Vec<_>>();
+        stats.kept += candidates.len() - to_prune.len();
+        let pruned = prune_addresses(conn, &to_prune, policy.config.archive, started).await?;
+        if policy.config.archive {
+            stats.archived += pruned;
+        } else {
+            stats.deleted += pruned;
+        }
+    }
+    stats.finished = chrono::Utc::now().naive_utc();
+    Ok(stats)
+}
+
+/// Periodic retention task, stats are shared with API
+pub async fn retention_loop(
+    conn: SqlitePool,
+    policy: RetentionPolicy,
+    stats: Arc<parking_lot::Mutex<RetentionStats>>,
+) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(policy.config.interval_secs.max(1)));
+    loop {
+        interval.tick().await;
+        let result = run_retention(&conn, &policy).await;
+        let mut stats = stats.lock();
+        stats.runs += 1;
+        match result {
+            Ok(run) => {
+                log::info!(
+                    "Retention scanned {} records, kept {}, deleted {}, archived {}",
+                    run.scanned,
+                    run.kept,
+                    run.deleted,
+                    run.archived
+                );
+                stats.total_deleted += run.deleted as u64;
+                stats.total_archived += run.archived as u64;
+                stats.last_run = Some(run);
+                stats.last_error = None;
+            }
+            Err(err) => {
+                log::error!("Retention run failed: {err}");
+                stats.last_error = Some(err.to_string());
+            }
+        }
+    }
+}
+
+pub async fn count_archived(conn: &SqlitePool) -> Result<i64, AddressologyError> {
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM fancy_archive")
+        .fetch_one(conn)
+        .await
+        .map_err(err_from!())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::connection::create_sqlite_connection;
+    use crate::db::model::OwnershipChange;
+    use crate::db::ops::{get_fancy_obj, insert_fancy_obj, test_fancy_obj};
+    use crate::db::ownership::change_ownership;
+
+    #[tokio::test]
+    async fn test_retention() {
+        let conn = create_sqlite_connection(None, true).await.unwrap();
+        let old = chrono::Utc::now().naive_utc() - chrono::Duration::days(100);
+        let mut stored = Vec::new();
+        for salt in 0..200 {
+            let mut fancy = test_fancy_obj(salt);
+            // the last few are fresh
+            if salt < 190 {
+                fancy.created = old;
+            }
+            // stored score decides, so top records do not depend on scoring details
+            if salt % 10 == 5 {
+                fancy.score = 1e15;
+            }
+            insert_fancy_obj(&conn, &fancy).await.unwrap();
+            stored.push(fancy);
+        }
+        let keep_min_score = 1e15;
+        let top = stored[..190]
+            .iter()
+            .filter(|f| f.score >= keep_min_score)
+            .count();
+        assert_eq!(top, 19);
+
+        let owned = stored[0].address;
+        change_ownership(
+            &conn,
+            owned,
+            &OwnershipChange::Assign {
+                owner: "acme".to_string(),
+            },
+            "test",
+        )
+        .await
+        .unwrap();
+        let reserved = stored[1].address;
+        change_ownership(
+            &conn,
+            reserved,
+            &OwnershipChange::Reserve {
+                reserved_by: "acme".to_string(),
+                expires_in_secs: 3600,
+            },
+            "test",
+        )
+        .await
+        .unwrap();
+
+        assert!(RetentionPolicy::from_config(&RetentionConfig {
+            keep_categories: BTreeMap::from([("nope".to_string(), 1.0)]),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(RetentionPolicy::from_config(&RetentionConfig {
+            enabled: true,
+            sample_percent: 20.0,
+            ..Default::default()
+        })
+        .is_err());
+        assert!(RetentionConfig::default().archive);
+
+        // owner assigned after scan is checked again when pruning
+        let now = chrono::Utc::now().naive_utc();
+        assert_eq!(
+            prune_addresses(&conn, &[owned, reserved], true, now)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(count_archived(&conn).await.unwrap(), 0);
+        let policy = RetentionPolicy::from_config(&RetentionConfig {
+            enabled: true,
+            keep_min_score: Some(keep_min_score),
+            sample_percent: 20.0,
+            archive: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let run = run_retention(&conn, &policy).await.unwrap();
+        assert_eq!(run.scanned, 188);
+        assert_eq!(run.kept + run.archived, run.scanned);
+        assert_eq!(run.deleted, 0);
+        // top scores plus roughly a fifth of the rest
+        assert!(
+            run.kept >= top + 15 && run.kept <= top + 2 + 60,
+            "kept {}",
+            run.kept
+        );
+        assert_eq!(count_archived(&conn).await.unwrap(), run.archived as i64);
+        for fancy in &stored {
+            let present = get_fancy_obj(&conn, fancy.address).await.unwrap().is_some();
+            if fancy.address == owned
+                || fancy.address == reserved
+                || fancy.created != old
+                || fancy.score >= keep_min_score
+            {
+                assert!(present);
+            }
+        }
+
+        // sampling is stable, second run removes nothing
+        let run = run_retention(&conn, &policy).await.unwrap();
+        assert_eq!(run.archived, 0);
+    }
+}
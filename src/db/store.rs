@@ -0,0 +1,31 @@
+use crate::db::ops::insert_fancy_obj;
+use crate::fancy::{get_pricing, score_fancy, FancyDbObj};
+use sqlx::SqlitePool;
+use tokio::sync::mpsc::Receiver;
+
+/// Runner output for targets that are not verified in place comes without score
+fn complete_fancy_obj(mut fancy: FancyDbObj) -> FancyDbObj {
+    if fancy.category.is_empty() {
+        let score = score_fancy(fancy.address.addr());
+        fancy.score = score.total_score;
+        fancy.price = get_pricing().price(&score);
+        fancy.category = score.category;
+    }
+    if fancy.created == Default::default() {
+        fancy.created = chrono::Utc::now().naive_utc();
+    }
+    fancy
+}
+
+/// Store results sent by runners until all senders are dropped
+pub async fn store_results(conn: SqlitePool, mut receiver: Receiver<FancyDbObj>) {
+    while let Some(fancy) = receiver.recv().await {
+        let fancy = complete_fancy_obj(fancy);
+        match insert_fancy_obj(&conn, &fancy).await {
+            Ok(true) => log::debug!("Stored address {}", fancy.address),
+            Ok(false) => log::debug!("Address {} already stored", fancy.address),
+            Err(err) => log::error!("Failed to store address {}: {err}", fancy.address),
+        }
+    }
+    log::info!("Result store finished");
+}
@@ -0,0 +1,263 @@
+use crate::fancy::{score_fast, FancyScoreCategory, SCORED_CATEGORIES};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use web3::types::Address;
+
+/// Thresholds with fewer expected hits are too noisy to compare
+const MIN_EXPECTED_HITS: f64 = 50.0;
+
+/// Thresholds are powers of two, difficulty is counted in bucket of its log2
+const BUCKETS: usize = 128;
+
+/// Random is a fixed placeholder score, not a probability
+const SKIPPED_CATEGORIES: [FancyScoreCategory; 1] = [FancyScoreCategory::Random];
+
+/// Difficulty D claims that 1 in D random addresses scores at least that high,
+/// so for well calibrated formula P(difficulty >= D) <= 1 / D.
+/// Scores are discrete, so observed tail between two score levels is expected to be lower.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationRow {
+    pub category: String,
+    pub threshold: f64,
+    pub expected_hits: f64,
+    pub observed_hits: u64,
+    /// observed / expected, above 1 means formula claims address is rarer than it is
+    pub ratio: f64,
+    pub z_score: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CalibrationReport {
+    pub samples: u64,
+    pub seed: u64,
+    pub rows: Vec<CalibrationRow>,
+}
+
+impl CalibrationReport {
+    /// Rows where formula overstates rarity (overprices) significantly beyond tolerance
+    pub fn drifted(&self, tolerance: f64) -> Vec<&CalibrationRow> {
+        self.rows
+            .iter()
+            .filter(|row| {
+                row.observed_hits as f64 > row.expected_hits * (1.0 + tolerance)
+                    && row.z_score > 3.0
+            })
+            .collect()
+    }
+}
+
+/// Index of the biggest power of two threshold reached by difficulty, 0 when below 2
+fn threshold_bucket(difficulty: f64) -> usize {
+    // NaN is not reaching any threshold either
+    if difficulty.is_nan() || difficulty < 2.0 {
+        return 0;
+    }
+    // exponent of f64 is floor(log2) without rounding errors of log2
+    let exponent = ((difficulty.to_bits() >> 52) & 0x7ff) as usize - 1023;
+    exponent.min(BUCKETS - 1)
+}
+
+/// Number of samples in every threshold bucket for every category
+fn sample_difficulties(samples: u64, seed: u64) -> BTreeMap<String, Vec<u64>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut buckets = vec![vec![0u64; BUCKETS]; SCORED_CATEGORIES.len()];
+    for _ in 0..samples {
+        let score = score_fast(&Address::from(rng.random::<[u8; 20]>()));
+        for (hits, entry) in buckets.iter_mut().zip(score.entries.iter()) {
+            hits[threshold_bucket(entry.difficulty)] += 1;
+        }
+    }
+    SCORED_CATEGORIES
+        .iter()
+        .zip(buckets)
+        .filter(|(category, _)| !SKIPPED_CATEGORIES.contains(category))
+        .map(|(category, hits)| (category.to_string(), hits))
+        .collect()
+}
+
+/// Score random addresses and compare tail of every category with its difficulty formula.
+/// Every thread samples its own seeded stream, so report is reproducible for the same seed and threads.
+pub fn calibrate_scores(samples: u64, threads: usize, seed: u64) -> CalibrationReport {
+    let threads = threads.max(1) as u64;
+    let per_thread = samples.div_ceil(threads);
+    let mut buckets: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+    std::thread::scope(|scope| {
+        let handles = (0..threads)
+            .map(|thread_no| {
+                let count = per_thread.min(samples.saturating_sub(thread_no * per_thread));
+                scope.spawn(move || sample_difficulties(count, seed.wrapping_add(thread_no)))
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            for (category, hits) in handle.join().expect("calibration thread panicked") {
+                let total = buckets.entry(category).or_insert_with(|| vec![0; BUCKETS]);
+                for (total, hits) in total.iter_mut().zip(hits) {
+                    *total += hits;
+                }
+            }
+        }
+    });
+
+    let mut rows = Vec::new();
+    for (category, hits) in buckets {
+        let total = hits.iter().sum::<u64>() as f64;
+        let mut threshold = 2.0;
+        let mut bucket = 1;
+        while total / threshold >= MIN_EXPECTED_HITS {
+            let expected_hits = total / threshold;
+            let observed_hits = hits[bucket..].iter().sum::<u64>();
+            rows.push(CalibrationRow {
+                category: category.clone(),
+                threshold,
+                expected_hits,
+                observed_hits,
+                ratio: observed_hits as f64 / expected_hits,
+                z_score: (observed_hits as f64 - expected_hits) / expected_hits.sqrt(),
+            });
+            threshold *= 2.0;
+            bucket += 1;
+        }
+    }
+    CalibrationReport {
+        samples,
+        seed,
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fancy::{address_to_mixed_case, FastScoreEntry};
+    use web3::types::U256;
+
+    #[test]
+    fn test_calibration_regression() {
+        // thresholds up to samples / MIN_EXPECTED_HITS are checked, about 6500 here
+        let report = calibrate_scores(400_000, 4, 1);
+        assert_eq!(report.samples, 400_000);
+        let leading_zeroes = report
+            .rows
+            .iter()
+            .filter(|row| row.category == "leading_zeroes")
+            .collect::<Vec<_>>();
+        assert_eq!(leading_zeroes.len(), 12);
+        assert!(leading_zeroes
+            .iter()
+            .all(|row| (row.ratio - 1.0).abs() < 0.5));
+        assert!(!report.rows.iter().any(|row| row.category == "random"));
+
+        let drifted = report.drifted(0.25);
+        assert!(drifted.is_empty(), "{drifted:#?}");
+    }
+
+    #[test]
+    fn test_threshold_bucket() {
+        assert_eq!(threshold_bucket(f64::NAN), 0);
+        assert_eq!(threshold_bucket(1.9), 0);
+        assert_eq!(threshold_bucket(2.0), 1);
+        assert_eq!(threshold_bucket(1023.9), 9);
+        assert_eq!(threshold_bucket(1024.0), 10);
+        assert_eq!(threshold_bucket(f64::INFINITY), BUCKETS - 1);
+    }
+
+    fn entry_of(address: &Address, category: FancyScoreCategory) -> FastScoreEntry {
+        let index = SCORED_CATEGORIES
+            .iter()
+            .position(|c| *c == category)
+            .unwrap();
+        score_fast(address).entries[index]
+    }
+
+    fn difficulty_of(address: &Address, category: FancyScoreCategory) -> f64 {
+        entry_of(address, category).difficulty
+    }
+
+    #[test]
+    fn test_leading_letters_tail() {
+        // long runs are too rare for random sampling, so lowercase run is forced
+        // and only checksum case is sampled: P(run >= n) = 6/16 * 16^-(n-1) * P(same case)
+        let mut rng = StdRng::seed_from_u64(1);
+        for length in 3..=7 {
+            let samples = 40_000;
+            let mut same_case = 0;
+            let mut difficulty = 0.0;
+            for _ in 0..samples {
+                let mut bytes = rng.random::<[u8; 20]>();
+                let letter = rng.random_range(0xa..=0xfu8);
+                for nibble in 0..length {
+                    let shift = if nibble % 2 == 0 { 4 } else { 0 };
+                    bytes[nibble / 2] = bytes[nibble / 2] & !(0xf << shift) | letter << shift;
+                }
+                let address = Address::from(bytes);
+                let mixed = address_to_mixed_case(&address);
+                let run = &mixed.trim_start_matches("0x")[..length];
+                if run.chars().all(|c| c == run.chars().next().unwrap()) {
+                    same_case += 1;
+                    let entry = entry_of(&address, FancyScoreCategory::LeadingLetters);
+                    if entry.score == length as f64 {
+                        difficulty = entry.difficulty;
+                    }
+                }
+            }
+            let same_case = same_case as f64 / samples as f64;
+            assert!((same_case * 2.0f64.powi(length as i32 - 1) - 1.0).abs() < 0.2);
+
+            // difficulty of exactly n letters, longer runs only make tail bigger
+            assert_eq!(difficulty, 32.0f64.powf(length as f64 - 15.0 / 16.0));
+            let tail = 6.0 / 16.0 * 16.0f64.powi(1 - length as i32) * same_case;
+            // 32^(1/16) * 6/16, formula prices run at about half of its rarity
+            assert!(tail * difficulty <= 1.0, "{length}: {}", tail * difficulty);
+            assert!(tail * difficulty > 0.35, "{length}: {}", tail * difficulty);
+        }
+    }
+
+    #[test]
+    fn test_leading_any_tail() {
+        // difficulty only depends on distance to the nearest repeated digit address,
+        // addresses within that distance of any of them are counted exactly
+        let to_address = |number: U256| {
+            let mut bytes = [0u8; 32];
+            number.to_big_endian(&mut bytes);
+            Address::from_slice(&bytes[12..])
+        };
+        let repeated = |digit: u64| {
+            U256::from_str_radix("1111111111111111111111111111111111111111", 16).unwrap()
+                * U256::from(digit)
+        };
+        for distance in [1u64 << 20, 1 << 40, 1 << 60] {
+            let distance = U256::from(distance) * U256::from(1u64 << 60);
+            let difficulty = difficulty_of(
+                &to_address(repeated(7) + distance),
+                FancyScoreCategory::LeadingAny,
+            );
+            // same distance on the other side and around any other digit
+            for digit in 1..15 {
+                for number in [repeated(digit) + distance, repeated(digit) - distance] {
+                    let other = difficulty_of(&to_address(number), FancyScoreCategory::LeadingAny);
+                    assert!((other / difficulty - 1.0).abs() < 1e-9);
+                }
+            }
+            let further = difficulty_of(
+                &to_address(repeated(7) + distance + distance / 1000),
+                FancyScoreCategory::LeadingAny,
+            );
+            assert!(further < difficulty);
+
+            // 14 digits with both sides, 0 and f only with one side
+            let count = distance.low_u128() as f64 * 30.0;
+            let tail = count / 2.0f64.powi(160);
+            // max_number already spreads address space over the 15 gaps between repeated digits,
+            // /15 counts them once more, so real tail is 15 times lower than difficulty claims
+            assert!(
+                (tail * difficulty * 15.0 - 1.0).abs() < 1e-6,
+                "{}",
+                tail * difficulty
+            );
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use crate::fancy::{
+    exactly_letters_combinations_difficulty, palindrome_difficulty, periodic_difficulty,
+    sequence_difficulty, snake_difficulty, total_combinations, zero_bytes_difficulty,
+    FancyScoreCategory,
+};
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// What are we looking for, converted to expected number of tries by difficulty()
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum EstimateTarget {
+    /// Expected number of addresses to check for one hit
+    Difficulty(f64),
+    /// Score in category at least min_score, like 10 leading zeroes
+    #[serde(rename_all = "camelCase")]
+    Category {
+        category: FancyScoreCategory,
+        min_score: f64,
+    },
+    /// Hex prefix of address, ? or x matches any character
+    Prefix(String),
+}
+
+/// Difficulty of reaching min_score in category, same formulas as score_fancy uses
+pub fn category_difficulty(
+    category: &FancyScoreCategory,
+    min_score: f64,
+) -> Result<f64, AddressologyError> {
+    if !(0.0..=40.0).contains(&min_score) {
+        return Err(err_custom_create!(
+            "Score has to be between 0 and 40, got {min_score}"
+        ));
+    }
+    let score = min_score.ceil() as u64;
+    let difficulty = match category {
+        FancyScoreCategory::LeadingZeroes
+        | FancyScoreCategory::LeadingAny
+        | FancyScoreCategory::ShortLeadingZeroes => total_combinations(score as f64),
+        FancyScoreCategory::LettersHeavy => exactly_letters_combinations_difficulty(score, 40),
+        FancyScoreCategory::SnakeScoreNoCase => snake_difficulty(score as i64, 40),
+        FancyScoreCategory::ZeroBytes => zero_bytes_difficulty(score.min(20), 20),
+        FancyScoreCategory::PeriodicRepeat => periodic_difficulty(score, 40),
+        FancyScoreCategory::SequenceRun => sequence_difficulty(score, 40),
+        FancyScoreCategory::Palindrome => palindrome_difficulty(score, 40),
+        other => {
+            return Err(err_custom_create!(
+                "Estimate for category {other} is not supported, use difficulty instead"
+            ))
+        }
+    };
+    Ok(difficulty.max(1.0))
+}
+
+impl EstimateTarget {
+    pub fn difficulty(&self) -> Result<f64, AddressologyError> {
+        match self {
+            EstimateTarget::Difficulty(difficulty) => {
+                if !difficulty.is_finite() || *difficulty < 1.0 {
+                    return Err(err_custom_create!(
+                        "Difficulty has to be finite number at least 1, got {difficulty}"
+                    ));
+                }
+                Ok(*difficulty)
+            }
+            EstimateTarget::Category {
+                category,
+                min_score,
+            } => category_difficulty(category, *min_score),
+            EstimateTarget::Prefix(prefix) => {
+                let prefix = prefix.trim_start_matches("0x");
+                if prefix.len() > 40 {
+                    return Err(err_custom_create!("Prefix cannot be longer than address"));
+                }
+                let mut fixed = 0;
+                for c in prefix.chars() {
+                    match c {
+                        '?' | 'x' | 'X' => {}
+                        c if c.is_ascii_hexdigit() => fixed += 1,
+                        c => {
+                            return Err(err_custom_create!("Invalid character {c} in prefix"));
+                        }
+                    }
+                }
+                Ok(total_combinations(fixed as f64))
+            }
+        }
+    }
+}
+
+/// Hits are Poisson process with rate speed / difficulty, so time to first hit is exponential
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeToFindEstimate {
+    pub difficulty: f64,
+    /// Aggregate speed in MH/s, the unit runners report
+    pub speed_mhs: f64,
+    pub expected_seconds: f64,
+    pub p50_seconds: f64,
+    pub p90_seconds: f64,
+    pub p99_seconds: f64,
+    pub hits_per_day: f64,
+}
+
+pub fn estimate_time_to_find(
+    target: &EstimateTarget,
+    speed_mhs: f64,
+) -> Result<TimeToFindEstimate, AddressologyError> {
+    if !speed_mhs.is_finite() || speed_mhs <= 0.0 {
+        return Err(err_custom_create!(
+            "Speed has to be positive, got {speed_mhs} MH/s"
+        ));
+    }
+    let difficulty = target.difficulty()?;
+    let expected_seconds = difficulty / (speed_mhs * 1_000_000.0);
+    let quantile = |p: f64| -(1.0 - p).ln() * expected_seconds;
+    Ok(TimeToFindEstimate {
+        difficulty,
+        speed_mhs,
+        expected_seconds,
+        p50_seconds: quantile(0.5),
+        p90_seconds: quantile(0.9),
+        p99_seconds: quantile(0.99),
+        hits_per_day: SECONDS_PER_DAY / expected_seconds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_time_to_find() {
+        let target = EstimateTarget::Category {
+            category: FancyScoreCategory::LeadingZeroes,
+            min_score: 10.0,
+        };
+        assert_eq!(target.difficulty().unwrap(), 16.0f64.powi(10));
+        assert_eq!(
+            EstimateTarget::Prefix("0x00000000??00".to_string())
+                .difficulty()
+                .unwrap(),
+            16.0f64.powi(10)
+        );
+
+        // 16^10 at 1000 MH/s is about 18 minutes
+        let estimate = estimate_time_to_find(&target, 1000.0).unwrap();
+        assert!((estimate.expected_seconds - 1099.511627776).abs() < 1e-6);
+        assert!((estimate.p50_seconds / estimate.expected_seconds - 2f64.ln()).abs() < 1e-12);
+        assert!(estimate.p50_seconds < estimate.expected_seconds);
+        assert!(estimate.p90_seconds < estimate.p99_seconds);
+        assert!((estimate.hits_per_day * estimate.expected_seconds - 86400.0).abs() < 1e-6);
+
+        assert!(estimate_time_to_find(&target, 0.0).is_err());
+        assert!(EstimateTarget::Prefix("0xzz".to_string())
+            .difficulty()
+            .is_err());
+        assert!(EstimateTarget::Category {
+            category: FancyScoreCategory::Random,
+            min_score: 1.0,
+        }
+        .difficulty()
+        .is_err());
+    }
+}
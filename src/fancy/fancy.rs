@@ -1,10 +1,14 @@
-use crate::config::get_base_difficulty_price;
-
 use crate::err_custom_create;
 use crate::error::AddressologyError;
-use crate::fancy::{score_fancy, FancyDbObj};
-use crate::hash::{compute_address_command, compute_create3_command};
+use crate::fancy::{
+    describe_hook_flags, get_pricing, score_fancy, FancyDbObj, FancyDbObjMin, HookFlagsTarget,
+};
+use crate::hash::{
+    compute_address_command, compute_create2, compute_create3, compute_create3_command,
+    Create3Scheme,
+};
 use crate::types::DbAddress;
+use std::str::FromStr;
 use web3::types::Address;
 
 #[allow(unused)]
@@ -35,14 +39,18 @@ pub fn parse_fancy_private(
         address,
         salt: private_key_add,
         factory: None,
+        init_code_hash: None,
         created: chrono::Utc::now().naive_utc(),
         score: score.total_score,
 
         owner: None,
-        price: (score.price_multiplier * get_base_difficulty_price() as f64) as i64,
+        price: get_pricing().price(&score),
         category: score.category,
         job: None,
         public_key_base: Some(public_key_base),
+        create3_scheme: None,
+        caller: None,
+        chain_id: None,
     })
 }
 
@@ -61,7 +69,36 @@ pub fn parse_fancy(salt: String, factory: Address) -> Result<FancyDbObj, Address
         });
     */
     let address = compute_create3_command(&format!("{:#x}", factory), &salt)?;
+    fancy_from_address(address, salt, factory)
+}
 
+#[allow(unused)]
+pub fn parse_fancy_create3(
+    salt: String,
+    factory: Address,
+    scheme: Create3Scheme,
+    caller: Option<Address>,
+    chain_id: Option<u64>,
+) -> Result<FancyDbObj, AddressologyError> {
+    let address = compute_create3(
+        &format!("{:#x}", factory),
+        &salt,
+        scheme,
+        caller.map(|caller| format!("{:#x}", caller)).as_deref(),
+        chain_id,
+    )?;
+    let mut fancy = fancy_from_address(address, salt, factory)?;
+    fancy.create3_scheme = Some(scheme.to_string());
+    fancy.caller = caller.map(DbAddress::wrap);
+    fancy.chain_id = chain_id.map(|chain_id| chain_id as i64);
+    Ok(fancy)
+}
+
+fn fancy_from_address(
+    address: String,
+    salt: String,
+    factory: Address,
+) -> Result<FancyDbObj, AddressologyError> {
     let address =
         DbAddress::from_str(&address).map_err(|_| err_custom_create!("Failed to parse address"))?;
 
@@ -71,17 +108,114 @@ pub fn parse_fancy(salt: String, factory: Address) -> Result<FancyDbObj, Address
         address,
         salt,
         factory: Some(DbAddress::wrap(factory)),
+        init_code_hash: None,
         created: chrono::Utc::now().naive_utc(),
         score: score.total_score,
 
         owner: None,
-        price: (score.price_multiplier * get_base_difficulty_price() as f64) as i64,
+        price: get_pricing().price(&score),
         category: score.category,
         job: None,
         public_key_base: None,
+        create3_scheme: None,
+        caller: None,
+        chain_id: None,
     })
 }
 
+#[allow(unused)]
+pub fn parse_fancy_create2(
+    salt: String,
+    deployer: Address,
+    init_code_hash: String,
+) -> Result<FancyDbObj, AddressologyError> {
+    let address = compute_create2(&format!("{:#x}", deployer), &salt, &init_code_hash)?;
+
+    let address =
+        DbAddress::from_str(&address).map_err(|_| err_custom_create!("Failed to parse address"))?;
+
+    let score = score_fancy(address.addr());
+
+    Ok(FancyDbObj {
+        address,
+        salt,
+        factory: Some(DbAddress::wrap(deployer)),
+        init_code_hash: Some(init_code_hash),
+        created: chrono::Utc::now().naive_utc(),
+        score: score.total_score,
+
+        owner: None,
+        price: get_pricing().price(&score),
+        category: score.category,
+        job: None,
+        public_key_base: None,
+        create3_scheme: None,
+        caller: None,
+        chain_id: None,
+    })
+}
+
+/// Verify CREATE3 salt and check that deployed address is valid Uniswap v4 hook with given flags
+pub fn parse_fancy_hook(
+    salt: String,
+    factory: Address,
+    hook_flags: &HookFlagsTarget,
+) -> Result<FancyDbObj, AddressologyError> {
+    let fancy = parse_fancy(salt, factory)?;
+    if !hook_flags.matches(&fancy.address.addr()) {
+        return Err(err_custom_create!(
+            "Address {} has hook flags {}, required: {}, forbidden: {}",
+            fancy.address,
+            describe_hook_flags(crate::fancy::hook_flags(&fancy.address.addr())),
+            describe_hook_flags(hook_flags.required),
+            describe_hook_flags(hook_flags.forbidden)
+        ));
+    }
+    Ok(fancy)
+}
+
+/// Recompute record from its inputs, the kind of address is guessed from filled fields
+pub fn recompute_fancy(record: &FancyDbObjMin) -> Result<FancyDbObj, AddressologyError> {
+    if let Some(scheme) = &record.create3_scheme {
+        let Some(factory) = record.factory else {
+            return Err(err_custom_create!(
+                "Record {} has create3 scheme but no factory",
+                record.address
+            ));
+        };
+        let scheme = Create3Scheme::from_str(scheme).map_err(|e| err_custom_create!("{e}"))?;
+        let chain_id = record
+            .chain_id
+            .map(u64::try_from)
+            .transpose()
+            .map_err(|_| err_custom_create!("Invalid chain id {:?}", record.chain_id))?;
+        return parse_fancy_create3(
+            record.salt.clone(),
+            factory.addr(),
+            scheme,
+            record.caller.map(|caller| caller.addr()),
+            chain_id,
+        );
+    }
+    match (
+        &record.public_key_base,
+        &record.factory,
+        &record.init_code_hash,
+    ) {
+        (Some(public_key_base), _, _) => {
+            parse_fancy_private(public_key_base.clone(), record.salt.clone())
+        }
+        (None, Some(deployer), Some(init_code_hash)) => {
+            parse_fancy_create2(record.salt.clone(), deployer.addr(), init_code_hash.clone())
+        }
+        (None, Some(factory), None) => parse_fancy(record.salt.clone(), factory.addr()),
+        (None, None, _) => Err(err_custom_create!(
+            "Record {} has neither factory nor public key base",
+            record.address
+        )),
+    }
+}
+
 //test fancy
 
 #[cfg(test)]
@@ -107,4 +241,107 @@ mod tests {
         );
         println!("{:?}", parsed);
     }
+
+    #[test]
+    fn test_parse_fancy_create2() {
+        let deployer = Address::from_str("0x00000000000000000000000000000000deadbeef").unwrap();
+        let salt = "0x00000000000000000000000000000000000000000000000000000000cafebabe";
+        // keccak256(0xdeadbeef)
+        let init_code_hash = "0xd4fd4e189132273036449fc9e11198c739161b4c0116a9a2dccdfa1c492006f1";
+
+        let parsed =
+            parse_fancy_create2(salt.to_string(), deployer, init_code_hash.to_string()).unwrap();
+        assert_eq!(
+            format!("{:#x}", parsed.address.addr()),
+            "0x60f3f640a8508fc6a86d45df051962668e1e8ac7"
+        );
+        assert_eq!(parsed.factory, Some(DbAddress::wrap(deployer)));
+        assert_eq!(parsed.init_code_hash.as_deref(), Some(init_code_hash));
+    }
+
+    #[test]
+    fn test_parse_fancy_hook() {
+        let salt = "0x9a07547b2ac4220006e585000000000000000000000000000000000000000000";
+        let factory = Address::from_str("0x9E3F8eaE49E442A323EF2094f277Bf62752E6995").unwrap();
+
+        // 0x...8882 - after add liquidity returns delta cannot be set without its base flag
+        let flags = crate::fancy::hook_flags(
+            &Address::from_str("0x31585b5cd5557777376822555552bb555ee18882").unwrap(),
+        );
+        assert_eq!(flags, 0x0882);
+        let result = parse_fancy_hook(
+            salt.to_string(),
+            factory,
+            &HookFlagsTarget::new(0x0880, 0).unwrap(),
+        );
+        assert!(result.is_err());
+
+        let any_hook = HookFlagsTarget::new(0, 0).unwrap();
+        let mut found = 0;
+        for i in 0..100u64 {
+            let salt = format!("0x{:064x}", i);
+            let address = parse_fancy(salt.clone(), factory).unwrap().address.addr();
+            let result = parse_fancy_hook(salt, factory, &any_hook);
+            assert_eq!(result.is_ok(), any_hook.matches(&address));
+            if result.is_ok() {
+                found += 1;
+            }
+        }
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn test_parse_fancy_create3() {
+        let factory = Address::from_str("0x9E3F8eaE49E442A323EF2094f277Bf62752E6995").unwrap();
+        let caller = Address::from_str("0x00000000000000000000000000000000deadbeef").unwrap();
+        let salt = "0x9a07547b2ac4220006e585000000000000000000000000000000000000000000";
+        let fancy = parse_fancy_create3(
+            salt.to_string(),
+            factory,
+            Create3Scheme::ZeframLou,
+            Some(caller),
+            None,
+        )
+        .unwrap();
+        // scheme and caller are kept, so the address can be recomputed later
+        assert_eq!(fancy.create3_scheme.as_deref(), Some("zeframlou"));
+        assert_eq!(fancy.caller, Some(DbAddress::wrap(caller)));
+        assert_ne!(
+            fancy.address,
+            parse_fancy(salt.to_string(), factory).unwrap().address
+        );
+    }
+
+    #[test]
+    fn test_recompute_fancy_create3() {
+        let factory = Address::from_str("0x9E3F8eaE49E442A323EF2094f277Bf62752E6995").unwrap();
+        let caller = Address::from_str("0x00000000000000000000000000000000deadbeef").unwrap();
+        let salt = "0x9a07547b2ac4220006e585000000000000000000000000000000000000000000";
+        let fancy = parse_fancy_create3(
+            salt.to_string(),
+            factory,
+            Create3Scheme::ZeframLou,
+            Some(caller),
+            None,
+        )
+        .unwrap();
+        let mut record = FancyDbObjMin {
+            address: fancy.address,
+            salt: fancy.salt.clone(),
+            factory: fancy.factory,
+            init_code_hash: None,
+            public_key_base: None,
+            create3_scheme: fancy.create3_scheme.clone(),
+            caller: fancy.caller,
+            chain_id: None,
+        };
+        assert_eq!(recompute_fancy(&record).unwrap().address, fancy.address);
+
+        // without stored scheme the factory default (plain salt) gives another address
+        record.create3_scheme = None;
+        assert_ne!(recompute_fancy(&record).unwrap().address, fancy.address);
+        record.create3_scheme = Some("zeframlou".to_string());
+        record.caller = None;
+        assert!(recompute_fancy(&record).is_err());
+    }
 }
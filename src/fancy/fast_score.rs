@@ -0,0 +1,481 @@
+use crate::fancy::{
+    exactly_letters_combinations_difficulty, get_hexspeak_dictionary, get_pricing,
+    palindrome_difficulty, periodic_difficulty, sequence_difficulty, snake_difficulty,
+    snake_need_case_difficulty, snake_need_letters_difficulty, total_combinations,
+    zero_bytes_difficulty, FancyScore, FancyScoreCategory, FancyScoreEntry,
+};
+use crate::fancy::{longest_palindrome, longest_periodic_segment, longest_sequence_run};
+use std::sync::OnceLock;
+use web3::signing::keccak256;
+use web3::types::{Address, U256};
+
+static SCORE_TABLES: OnceLock<ScoreTables> = OnceLock::new();
+
+/// Entries in the same order as score_fancy pushes them, first one wins on equal difficulty
+pub const SCORED_CATEGORIES: [FancyScoreCategory; 17] = [
+    FancyScoreCategory::Random,
+    FancyScoreCategory::LeadingZeroes,
+    FancyScoreCategory::LeadingAny,
+    FancyScoreCategory::LettersHeavy,
+    FancyScoreCategory::NumbersOnly,
+    FancyScoreCategory::ShortLeadingZeroes,
+    FancyScoreCategory::ShortLeadingAny,
+    FancyScoreCategory::SnakeScoreNoCase,
+    FancyScoreCategory::SnakeScoreNeedCase,
+    FancyScoreCategory::SnakeScoreNeedLetters,
+    FancyScoreCategory::ZeroBytes,
+    FancyScoreCategory::Hexspeak,
+    FancyScoreCategory::PeriodicRepeat,
+    FancyScoreCategory::SequenceRun,
+    FancyScoreCategory::Palindrome,
+    FancyScoreCategory::LeadingLetters,
+    FancyScoreCategory::PatternScore,
+];
+
+// 0xffffffffffffffffffffffffffffffffffffffff
+const MAX_ADDRESS: U256 = U256([u64::MAX, u64::MAX, 0xffff_ffff, 0]);
+// 0x1111111111111111111111111111111111111111
+const ADDRESS_ONES: U256 = U256([0x1111_1111_1111_1111, 0x1111_1111_1111_1111, 0x1111_1111, 0]);
+const MAX_SHORT: u128 = 0xf_ffff_ffff_ffff_ffff;
+const SHORT_ONES: u128 = 0x1_1111_1111_1111_1111;
+
+/// Difficulty formulas depend only on small integer scores, so they are computed once
+/// by the same functions score_fancy uses
+struct ScoreTables {
+    letters_heavy: [f64; 41],
+    snake: [f64; 41],
+    snake_need_case: [f64; 41],
+    snake_need_letters: [f64; 41],
+    zero_bytes: [f64; 21],
+    periodic: [f64; 41],
+    sequence: [f64; 41],
+    palindrome: [f64; 41],
+}
+
+fn get_score_tables() -> &'static ScoreTables {
+    SCORE_TABLES.get_or_init(|| ScoreTables {
+        letters_heavy: std::array::from_fn(|i| {
+            exactly_letters_combinations_difficulty(i as u64, 40)
+        }),
+        snake: std::array::from_fn(|i| snake_difficulty(i as i64, 40)),
+        snake_need_case: std::array::from_fn(|i| snake_need_case_difficulty(i as i64, 40)),
+        snake_need_letters: std::array::from_fn(|i| snake_need_letters_difficulty(i as i64, 40)),
+        zero_bytes: std::array::from_fn(|i| zero_bytes_difficulty(i as u64, 20)),
+        periodic: std::array::from_fn(|i| periodic_difficulty(i as u64, 40)),
+        sequence: std::array::from_fn(|i| sequence_difficulty(i as u64, 40)),
+        palindrome: std::array::from_fn(|i| palindrome_difficulty(i as u64, 40)),
+    })
+}
+
+/// Correctly rounded conversion, same result as parsing decimal string of the number
+fn u256_to_f64(value: U256) -> f64 {
+    let bits = value.bits();
+    if bits <= 128 {
+        return value.low_u128() as f64;
+    }
+    let shift = bits - 128;
+    let mut top = (value >> shift).low_u128();
+    // keep information about dropped bits for rounding
+    if !(value & ((U256::one() << shift) - U256::one())).is_zero() {
+        top |= 1;
+    }
+    top as f64 * 2.0f64.powi(shift as i32)
+}
+
+fn leading_count(chars: &[u8], c: u8) -> usize {
+    chars.iter().take_while(|x| **x == c).count()
+}
+
+/// Non-overlapping occurrences, like str::matches
+fn count_matches(haystack: &[u8], needle: &[u8]) -> usize {
+    let mut count = 0;
+    let mut position = 0;
+    while position + needle.len() <= haystack.len() {
+        if &haystack[position..position + needle.len()] == needle {
+            count += 1;
+            position += needle.len();
+        } else {
+            position += 1;
+        }
+    }
+    count
+}
+
+fn zeroes_at(chars: &[u8], start: usize, len: usize) -> bool {
+    chars[start..start + len].iter().all(|c| *c == b'0')
+}
+
+/// Same values as snake counting in score_fancy: first char plus every char equal to previous,
+/// and number of letters equal to previous
+fn snake_count(chars: &[u8]) -> (i64, i64) {
+    let mut snake = 0;
+    let mut letter_pairs = 0;
+    let mut prev_char = chars[0];
+    for (i, c) in chars.iter().enumerate() {
+        if *c == prev_char {
+            snake += 1;
+            if i > 0 && c.is_ascii_alphabetic() {
+                letter_pairs += 1;
+            }
+        } else {
+            prev_char = *c;
+        }
+    }
+    (snake, letter_pairs)
+}
+
+fn leading_any_difficulty(current: U256) -> f64 {
+    let max_number = ADDRESS_ONES / U256::from(2);
+    let mut min_difference = max_number;
+    for digit in 0..16u64 {
+        let ideal_number = ADDRESS_ONES * U256::from(digit);
+        let difference = if ideal_number >= current {
+            ideal_number - current
+        } else {
+            current - ideal_number
+        };
+        min_difference = min_difference.min(difference);
+    }
+    u256_to_f64(max_number) / u256_to_f64(min_difference + U256::one()) / 15.0
+}
+
+fn short_leading_any_difficulty(current: u128) -> f64 {
+    let max_number = SHORT_ONES / 2;
+    let mut min_difference = max_number;
+    for digit in 0..16u128 {
+        min_difference = min_difference.min((SHORT_ONES * digit).abs_diff(current));
+    }
+    max_number as f64 / (min_difference + 1) as f64 / 15.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FastScoreEntry {
+    pub score: f64,
+    pub difficulty: f64,
+}
+
+/// Scores of all categories in SCORED_CATEGORIES order, computed without heap allocation
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastScore {
+    pub entries: [FastScoreEntry; SCORED_CATEGORIES.len()],
+    /// Index of the category with the biggest difficulty
+    pub best: usize,
+}
+
+impl FastScore {
+    pub fn category(&self) -> &'static FancyScoreCategory {
+        &SCORED_CATEGORIES[self.best]
+    }
+
+    pub fn total_score(&self) -> f64 {
+        self.entries[self.best].difficulty
+    }
+
+    /// Full score with address strings and price, identical to score_fancy
+    pub fn to_fancy_score(&self, address: Address) -> FancyScore {
+        let address_mixed_case = crate::fancy::address_to_mixed_case(&address);
+        let category = self.category().to_string();
+        FancyScore {
+            address_lower_case: format!("{:#x}", address),
+            address_short_etherscan: address_mixed_case[0..10].to_string()
+                + "..."
+                + &address_mixed_case[33..42],
+            address_mixed_case,
+            scores: SCORED_CATEGORIES
+                .iter()
+                .zip(self.entries.iter())
+                .map(|(category, entry)| {
+                    (
+                        category.to_string(),
+                        FancyScoreEntry {
+                            category: category.clone(),
+                            score: entry.score,
+                            difficulty: entry.difficulty,
+                        },
+                    )
+                })
+                .collect(),
+            total_score: self.total_score(),
+            price_multiplier: get_pricing().price_multiplier(&category, self.total_score()),
+            category,
+        }
+    }
+}
+
+/// Score address working on bytes and nibbles, used in hot loops (calibration, ingestion)
+pub fn score_fast(address: &Address) -> FastScore {
+    let tables = get_score_tables();
+    let bytes = address.as_bytes();
+
+    let mut nibbles = [0u8; 40];
+    let mut lower = [0u8; 40];
+    for (i, byte) in bytes.iter().enumerate() {
+        nibbles[2 * i] = byte >> 4;
+        nibbles[2 * i + 1] = byte & 0x0f;
+    }
+    for (c, nibble) in lower.iter_mut().zip(nibbles.iter()) {
+        *c = b"0123456789abcdef"[*nibble as usize];
+    }
+    let hash = keccak256(&lower);
+    let mut mixed = lower;
+    for (i, c) in mixed.iter_mut().enumerate() {
+        let hash_nibble = if i % 2 == 0 {
+            hash[i / 2] >> 4
+        } else {
+            hash[i / 2] & 0x0f
+        };
+        if hash_nibble > 7 {
+            c.make_ascii_uppercase();
+        }
+    }
+    let mut short = [0u8; 17];
+    short[..8].copy_from_slice(&mixed[..8]);
+    short[8..].copy_from_slice(&mixed[31..]);
+
+    let mut entries = [FastScoreEntry::default(); SCORED_CATEGORIES.len()];
+    entries[0] = FastScoreEntry {
+        score: 1.0,
+        difficulty: 1000.0,
+    };
+
+    let value = U256::from_big_endian(bytes);
+    let leading_zeroes = leading_count(&lower, b'0');
+    entries[1] = FastScoreEntry {
+        score: leading_zeroes as f64,
+        difficulty: u256_to_f64(MAX_ADDRESS) / u256_to_f64(value + U256::one()),
+    };
+
+    let leading_any = leading_count(&lower, lower[0]);
+    entries[2] = FastScoreEntry {
+        score: leading_any as f64 - 1.0,
+        difficulty: leading_any_difficulty(value),
+    };
+
+    let letters_heavy = lower.iter().filter(|c| c.is_ascii_alphabetic()).count();
+    entries[3] = FastScoreEntry {
+        score: letters_heavy as f64,
+        difficulty: tables.letters_heavy[letters_heavy],
+    };
+
+    let numbers_only = 40 - letters_heavy;
+    let numbers_only_difficulty = if numbers_only == 40 {
+        let decimal = nibbles
+            .iter()
+            .fold(U256::zero(), |acc, n| acc * U256::from(10) + U256::from(*n));
+        let number = u256_to_f64(decimal);
+        let max_number = 9999999999999999999999999999999999999999f64;
+        let difficulty1 =
+            total_combinations(40.0) / 10.0f64.powf(numbers_only as f64) / (number / max_number);
+        let difficulty2 = total_combinations(40.0)
+            / 10.0f64.powf(numbers_only as f64)
+            / ((max_number - number) / max_number);
+        difficulty1.max(difficulty2)
+    } else {
+        1.0
+    };
+    entries[4] = FastScoreEntry {
+        score: numbers_only as f64,
+        difficulty: numbers_only_difficulty,
+    };
+
+    let short_value = nibbles[..8]
+        .iter()
+        .chain(nibbles[31..].iter())
+        .fold(0u128, |acc, n| (acc << 4) | *n as u128);
+    entries[5] = FastScoreEntry {
+        score: leading_count(&short, b'0') as f64,
+        difficulty: MAX_SHORT as f64 / (short_value + 1) as f64,
+    };
+    entries[6] = FastScoreEntry {
+        score: leading_count(&short, short[0]) as f64,
+        difficulty: short_leading_any_difficulty(short_value + 1),
+    };
+
+    let (snake_score_no_case, _) = snake_count(&lower);
+    let (snake_score_mixed, snake_letter_pairs) = snake_count(&mixed);
+    let snake_score = (snake_score_no_case - 1) as f64;
+    entries[7] = FastScoreEntry {
+        score: snake_score,
+        difficulty: tables.snake[(snake_score_no_case - 1) as usize],
+    };
+    entries[8] = FastScoreEntry {
+        score: snake_score,
+        difficulty: tables.snake_need_case[(snake_score_mixed - 1) as usize],
+    };
+    entries[9] = FastScoreEntry {
+        score: snake_score,
+        difficulty: tables.snake_need_letters[snake_letter_pairs as usize],
+    };
+
+    let zero_bytes = bytes.iter().filter(|b| **b == 0).count();
+    entries[10] = FastScoreEntry {
+        score: zero_bytes as f64,
+        difficulty: tables.zero_bytes[zero_bytes],
+    };
+
+    let hexspeak = get_hexspeak_dictionary().best_match_difficulty(&lower, &mixed);
+    entries[11] = FastScoreEntry {
+        score: hexspeak.map(|(len, _)| len as f64).unwrap_or(0.0),
+        difficulty: hexspeak.map(|(_, difficulty)| difficulty).unwrap_or(1.0),
+    };
+
+    let periodic_segment = longest_periodic_segment(&nibbles) as usize;
+    entries[12] = FastScoreEntry {
+        score: periodic_segment as f64,
+        difficulty: tables.periodic[periodic_segment],
+    };
+    let sequence_run = longest_sequence_run(&nibbles) as usize;
+    entries[13] = FastScoreEntry {
+        score: sequence_run as f64,
+        difficulty: tables.sequence[sequence_run],
+    };
+    let palindrome = longest_palindrome(&nibbles) as usize;
+    entries[14] = FastScoreEntry {
+        score: palindrome as f64,
+        difficulty: tables.palindrome[palindrome],
+    };
+
+    let leading_letters = if mixed[0].is_ascii_alphabetic() {
+        leading_count(&mixed, mixed[0])
+    } else {
+        0
+    };
+    entries[15] = FastScoreEntry {
+        score: leading_letters as f64,
+        difficulty: 32.0f64.powf(leading_letters as f64 - (15. / 16.)),
+    };
+
+    let count_0bb50 = count_matches(&mixed, b"0BB50");
+    let mut pattern_score = count_0bb50 * 2
+        + (count_matches(&mixed, b"0BB5") - count_0bb50)
+        + (count_matches(&mixed, b"BB50") - count_0bb50);
+    // ^00000.{3}00000
+    if zeroes_at(&mixed, 0, 5) && zeroes_at(&mixed, 8, 5) {
+        pattern_score += 1000;
+    }
+    // 000000.{3}000000
+    if (0..=40 - 15).any(|start| zeroes_at(&mixed, start, 6) && zeroes_at(&mixed, start + 9, 6)) {
+        pattern_score += 20000;
+    }
+    if count_matches(&mixed, b"0000BB50000") > 0 {
+        pattern_score += 500;
+    }
+    entries[16] = FastScoreEntry {
+        score: pattern_score as f64,
+        difficulty: if pattern_score >= 6 {
+            pattern_score as f64 * 1.0E10
+        } else {
+            1.0
+        },
+    };
+
+    // strict comparison like score_fancy, so NaN never wins and first entry wins ties
+    let mut best = 0;
+    for (idx, entry) in entries.iter().enumerate() {
+        if entry.difficulty > entries[best].difficulty {
+            best = idx;
+        }
+    }
+    FastScore { entries, best }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fancy::score_fancy_reference;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::str::FromStr;
+
+    fn address_from_nibbles(nibbles: &[u8; 40]) -> Address {
+        let mut bytes = [0u8; 20];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (nibbles[2 * i] << 4) | nibbles[2 * i + 1];
+        }
+        Address::from(bytes)
+    }
+
+    /// Random address with planted structure, so rare branches are actually exercised
+    fn structured_address(rng: &mut StdRng) -> Address {
+        let mut nibbles: [u8; 40] = std::array::from_fn(|_| rng.random_range(0..16));
+        if rng.random_bool(0.2) {
+            nibbles.iter_mut().for_each(|n| *n %= 10);
+        }
+        for _ in 0..rng.random_range(0..4) {
+            let start = rng.random_range(0..40);
+            let len = rng.random_range(1..=40 - start);
+            let segment = &mut nibbles[start..start + len];
+            match rng.random_range(0..6) {
+                0 => segment.fill(0),
+                1 => segment.fill(rng.random_range(0..16)),
+                2 => {
+                    let first = rng.random_range(0..16u8);
+                    let step: u8 = if rng.random_bool(0.5) { 1 } else { 15 };
+                    for (i, n) in segment.iter_mut().enumerate() {
+                        *n = first.wrapping_add(step.wrapping_mul(i as u8)) & 0x0f;
+                    }
+                }
+                3 => {
+                    let period = rng.random_range(2..=8);
+                    for i in period..len {
+                        segment[i] = segment[i - period];
+                    }
+                }
+                4 => {
+                    for i in 0..len / 2 {
+                        segment[len - 1 - i] = segment[i];
+                    }
+                }
+                _ => {
+                    let words = get_hexspeak_dictionary().words();
+                    let pattern = &words[rng.random_range(0..words.len())].pattern;
+                    for (n, c) in segment.iter_mut().zip(pattern.chars()) {
+                        *n = c.to_digit(16).unwrap() as u8;
+                    }
+                }
+            }
+        }
+        address_from_nibbles(&nibbles)
+    }
+
+    #[test]
+    fn test_fast_score_equivalence() {
+        let mut edge_cases = vec![
+            Address::zero(),
+            Address::repeat_byte(0xff),
+            Address::repeat_byte(0x99),
+            Address::repeat_byte(0x11),
+            Address::repeat_byte(0x88),
+            Address::from_low_u64_be(1),
+            Address::from_str("0x00000abc000001234567890abcdef0000bb50000").unwrap(),
+            Address::from_str("0x000000123000000deadbeef0bb500bb50bb5bb50").unwrap(),
+        ];
+        let mut rng = StdRng::seed_from_u64(40);
+        edge_cases.extend((0..1000).map(|_| Address::from(rng.random::<[u8; 20]>())));
+        edge_cases.extend((0..2000).map(|_| structured_address(&mut rng)));
+        for address in edge_cases {
+            let fast = score_fast(&address);
+            assert_eq!(
+                fast.to_fancy_score(address),
+                score_fancy_reference(address),
+                "{address:#x}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_u256_to_f64() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let bits = rng.random_range(0..=160);
+            let value = U256::from_big_endian(&rng.random::<[u8; 32]>()) >> (256 - bits);
+            assert_eq!(
+                u256_to_f64(value),
+                value.to_string().parse::<f64>().unwrap()
+            );
+        }
+        // exactly between two floats rounds to even
+        let half = (U256::one() << 140) + (U256::one() << 87);
+        assert_eq!(u256_to_f64(half), half.to_string().parse::<f64>().unwrap());
+    }
+}
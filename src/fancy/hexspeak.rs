@@ -0,0 +1,405 @@
+use crate::config::get_hexspeak_word_list_path;
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+static HEXSPEAK_DICTIONARY: OnceLock<HexspeakDictionary> = OnceLock::new();
+
+/// Words used when no word list is configured (HEXSPEAK_WORD_LIST env)
+pub const DEFAULT_HEXSPEAK_WORDS: &[&str] = &[
+    "access", "accessed", "ace", "add", "added", "babe", "bad", "ball", "baseball", "be5t", "bed",
+    "bee", "beef", "bob", "boss", "cab", "cafe", "cafebabe", "cascade", "c0ffee", "code", "coded",
+    "cool", "dad", "dead", "deadbeef", "deaf", "decade", "decaf", "decode", "defaced", "face",
+    "facade", "fade", "faded", "feed", "food", "idea", "leet", "lost", "office", "salad",
+    "scalable", "seed", "sell", "slot", "steel", "toast", "tool",
+];
+
+/// Minimum length of hex pattern, shorter words are just noise.
+/// Short words are common, their difficulty counts all words of the same length
+const MIN_PATTERN_LEN: usize = 3;
+
+/// Hex characters of address, without 0x prefix
+const ADDRESS_LEN: usize = 40;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HexspeakWord {
+    pub word: String,
+    pub pattern: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HexspeakMatch {
+    pub word: String,
+    pub pattern: String,
+    pub position: usize,
+    pub case_exact: bool,
+    pub difficulty: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HexspeakDictionary {
+    words: Vec<HexspeakWord>,
+    /// Number of words for every pattern length, every one of them is a chance for a match
+    words_by_len: Vec<usize>,
+    /// Match classes (word, prefix or not, case exact or not) sorted by descending raw difficulty,
+    /// with expected number of matches of that class or any rarer one in random address
+    tail: Vec<(f64, f64)>,
+}
+
+/// Convert readable word to hex pattern using leet substitutions (o->0, l/i->1, s->5, t->7)
+pub fn word_to_hex_pattern(word: &str) -> Option<String> {
+    word.trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'a'..='f' | '0'..='9' => Some(c),
+            'o' => Some('0'),
+            'l' | 'i' => Some('1'),
+            's' => Some('5'),
+            't' => Some('7'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Readable forms of hex pattern, 1 can be read both as l and i
+fn pattern_to_readable(pattern: &str) -> Vec<String> {
+    ['l', 'i']
+        .iter()
+        .map(|one| {
+            pattern
+                .chars()
+                .map(|c| match c {
+                    '0' => 'o',
+                    '1' => *one,
+                    '5' => 's',
+                    '7' => 't',
+                    c => c,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Chance of getting one of candidate words of given length is candidates * 16^-len for every
+/// possible position, case exact match needs all letters in the same case
+/// (2 out of 2^letters combinations)
+pub fn hexspeak_difficulty(
+    pattern_len: usize,
+    positions: usize,
+    candidates: usize,
+    case_letters: usize,
+) -> f64 {
+    let mut difficulty =
+        16.0f64.powf(pattern_len as f64) / (positions.max(1) * candidates.max(1)) as f64;
+    if case_letters > 0 {
+        difficulty *= 2.0f64.powf(case_letters as f64 - 1.0);
+    }
+    difficulty
+}
+
+/// Expected number of matches of every class of the word, paired with raw difficulty of the class
+fn match_classes(pattern: &str, candidates: usize) -> Vec<(f64, f64)> {
+    let pattern_len = pattern.len();
+    let letters = pattern.chars().filter(|c| c.is_alphabetic()).count();
+    let case_exact_chance = if letters > 0 {
+        2.0f64.powf(1.0 - letters as f64)
+    } else {
+        0.0
+    };
+    let chance = 16.0f64.powf(-(pattern_len as f64));
+    let mut classes = Vec::new();
+    for (positions, count) in [
+        (1, 1),
+        (ADDRESS_LEN - pattern_len + 1, ADDRESS_LEN - pattern_len),
+    ] {
+        let expected = chance * count as f64;
+        classes.push((
+            hexspeak_difficulty(pattern_len, positions, candidates, letters),
+            expected * case_exact_chance,
+        ));
+        classes.push((
+            hexspeak_difficulty(pattern_len, positions, candidates, 0),
+            expected * (1.0 - case_exact_chance),
+        ));
+    }
+    classes
+}
+
+impl HexspeakDictionary {
+    pub fn from_words<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> Self {
+        let censor = censor::Standard + censor::Zealous + censor::Sex;
+
+        let mut patterns = BTreeMap::new();
+        for word in words {
+            let word = word.as_ref().trim().to_lowercase();
+            if word.is_empty() || word.starts_with('#') {
+                continue;
+            }
+            let Some(pattern) = word_to_hex_pattern(&word) else {
+                log::debug!("Skipping word {word} - it cannot be written in hex");
+                continue;
+            };
+            if pattern.len() < MIN_PATTERN_LEN || pattern.len() > ADDRESS_LEN {
+                continue;
+            }
+            if censor.check(&word)
+                || pattern_to_readable(&pattern)
+                    .iter()
+                    .any(|readable| censor.check(readable))
+            {
+                log::debug!("Skipping word {word} - filtered by profanity check");
+                continue;
+            }
+            patterns.entry(pattern).or_insert(word);
+        }
+        let mut words_by_len = vec![0; ADDRESS_LEN + 1];
+        for pattern in patterns.keys() {
+            words_by_len[pattern.len()] += 1;
+        }
+        let mut tail = patterns
+            .keys()
+            .flat_map(|pattern| match_classes(pattern, words_by_len[pattern.len()]))
+            .collect::<Vec<_>>();
+        tail.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut expected = 0.0;
+        for (_, class_expected) in tail.iter_mut() {
+            expected += *class_expected;
+            *class_expected = expected;
+        }
+        Self {
+            words: patterns
+                .into_iter()
+                .map(|(pattern, word)| HexspeakWord { word, pattern })
+                .collect(),
+            words_by_len,
+            tail,
+        }
+    }
+
+    pub fn load_from_file(path: &str) -> Result<Self, AddressologyError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| err_custom_create!("Failed to read word list {path}: {e}"))?;
+        Ok(Self::from_words(content.lines()))
+    }
+
+    pub fn words(&self) -> &[HexspeakWord] {
+        &self.words
+    }
+
+    /// Number of words with pattern of given length
+    pub fn candidates(&self, pattern_len: usize) -> usize {
+        self.words_by_len.get(pattern_len).copied().unwrap_or(0)
+    }
+
+    /// Short words alone are common, but there are many of them (and many positions for them),
+    /// so difficulty of a match is chance of getting match of at least the same raw difficulty
+    /// anywhere in random address, counting every word, position and case of the dictionary
+    pub fn tail_difficulty(&self, raw_difficulty: f64) -> f64 {
+        let rarer = self.tail.partition_point(|(raw, _)| *raw >= raw_difficulty);
+        let expected = match rarer {
+            0 => return raw_difficulty,
+            rarer => self.tail[rarer - 1].1,
+        };
+        1.0 / (1.0 - (-expected).exp())
+    }
+
+    /// Find all words in address, address strings are expected without 0x prefix
+    pub fn find_matches(&self, address_lower: &str, address_mixed: &str) -> Vec<HexspeakMatch> {
+        let mut matches = Vec::new();
+        for word in self.words.iter() {
+            let pattern_len = word.pattern.len();
+            if pattern_len > address_lower.len() {
+                continue;
+            }
+            for (position, _) in address_lower.match_indices(&word.pattern) {
+                let mixed_part = &address_mixed[position..position + pattern_len];
+                let letters = mixed_part.chars().filter(|c| c.is_alphabetic()).count();
+                let case_exact = letters > 0
+                    && (mixed_part.chars().all(|c| !c.is_ascii_uppercase())
+                        || mixed_part.chars().all(|c| !c.is_ascii_lowercase()));
+                let positions = if position == 0 {
+                    1
+                } else {
+                    address_lower.len() - pattern_len + 1
+                };
+                matches.push(HexspeakMatch {
+                    word: word.word.clone(),
+                    pattern: word.pattern.clone(),
+                    position,
+                    case_exact,
+                    difficulty: self.tail_difficulty(hexspeak_difficulty(
+                        pattern_len,
+                        positions,
+                        self.candidates(pattern_len),
+                        if case_exact { letters } else { 0 },
+                    )),
+                });
+            }
+        }
+        matches
+    }
+
+    pub fn best_match(&self, address_lower: &str, address_mixed: &str) -> Option<HexspeakMatch> {
+        let mut best: Option<HexspeakMatch> = None;
+        for m in self.find_matches(address_lower, address_mixed) {
+            if best.as_ref().is_none_or(|b| m.difficulty > b.difficulty) {
+                best = Some(m);
+            }
+        }
+        best
+    }
+
+    /// Pattern length and difficulty of best_match, without allocating the match
+    pub fn best_match_difficulty(
+        &self,
+        address_lower: &[u8],
+        address_mixed: &[u8],
+    ) -> Option<(usize, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        for word in self.words.iter() {
+            let pattern = word.pattern.as_bytes();
+            let pattern_len = pattern.len();
+            let mut position = 0;
+            // same non-overlapping occurrences as str::match_indices
+            while position + pattern_len <= address_lower.len() {
+                if &address_lower[position..position + pattern_len] != pattern {
+                    position += 1;
+                    continue;
+                }
+                let mixed_part = &address_mixed[position..position + pattern_len];
+                let letters = mixed_part
+                    .iter()
+                    .filter(|c| c.is_ascii_alphabetic())
+                    .count();
+                let case_exact = letters > 0
+                    && (mixed_part.iter().all(|c| !c.is_ascii_uppercase())
+                        || mixed_part.iter().all(|c| !c.is_ascii_lowercase()));
+                let positions = if position == 0 {
+                    1
+                } else {
+                    address_lower.len() - pattern_len + 1
+                };
+                let difficulty = self.tail_difficulty(hexspeak_difficulty(
+                    pattern_len,
+                    positions,
+                    self.candidates(pattern_len),
+                    if case_exact { letters } else { 0 },
+                ));
+                if best.is_none_or(|(_, b)| difficulty > b) {
+                    best = Some((pattern_len, difficulty));
+                }
+                position += pattern_len;
+            }
+        }
+        best
+    }
+}
+
+pub fn get_hexspeak_dictionary() -> &'static HexspeakDictionary {
+    HEXSPEAK_DICTIONARY.get_or_init(|| {
+        if let Some(path) = get_hexspeak_word_list_path() {
+            match HexspeakDictionary::load_from_file(&path) {
+                Ok(dictionary) => {
+                    log::info!(
+                        "Loaded {} hexspeak words from {path}",
+                        dictionary.words().len()
+                    );
+                    return dictionary;
+                }
+                Err(err) => {
+                    log::error!("Failed to load hexspeak word list, using default: {err}");
+                }
+            }
+        }
+        HexspeakDictionary::from_words(DEFAULT_HEXSPEAK_WORDS.iter())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_to_hex_pattern() {
+        assert_eq!(word_to_hex_pattern("coffee").unwrap(), "c0ffee");
+        assert_eq!(word_to_hex_pattern("baseball").unwrap(), "ba5eba11");
+        assert_eq!(word_to_hex_pattern("bob").unwrap(), "b0b");
+        assert_eq!(word_to_hex_pattern("c0ffee").unwrap(), "c0ffee");
+        assert_eq!(word_to_hex_pattern("toast").unwrap(), "70a57");
+        assert!(word_to_hex_pattern("golem").is_none());
+    }
+
+    #[test]
+    fn test_find_matches() {
+        let dictionary =
+            HexspeakDictionary::from_words(["deadbeef", "cafe", "face", "bad", "boobs"]);
+        assert_eq!(dictionary.words().len(), 4);
+        assert_eq!(dictionary.candidates(4), 2);
+        assert_eq!(dictionary.candidates(3), 1);
+
+        let lower = "deadbeef00000000000000000000000000cafe00";
+        let mixed = "DEADBEEF00000000000000000000000000CafE00";
+        let matches = dictionary.find_matches(lower, mixed);
+        assert_eq!(matches.len(), 2);
+
+        let deadbeef = matches.iter().find(|m| m.word == "deadbeef").unwrap();
+        assert_eq!(deadbeef.position, 0);
+        assert!(deadbeef.case_exact);
+        assert_eq!(
+            deadbeef.difficulty,
+            dictionary.tail_difficulty(hexspeak_difficulty(8, 1, 1, 8))
+        );
+        // nothing is rarer than case exact prefix of the longest word
+        assert!((deadbeef.difficulty / hexspeak_difficulty(8, 1, 1, 8) - 1.0).abs() < 1e-6);
+
+        let cafe = matches.iter().find(|m| m.word == "cafe").unwrap();
+        assert_eq!(cafe.position, 34);
+        assert!(!cafe.case_exact);
+        assert_eq!(
+            cafe.difficulty,
+            dictionary.tail_difficulty(hexspeak_difficulty(4, 37, 2, 0))
+        );
+        // any 4-letter word anywhere, bad as prefix or deadbeef anywhere
+        let expected = (2.0 * 37.0 + 16.0) / 65536.0 + 33.0 / 16.0f64.powf(8.0);
+        assert!((cafe.difficulty * (1.0 - (-expected).exp()) - 1.0).abs() < 1e-9);
+
+        let best = dictionary.best_match(lower, mixed).unwrap();
+        assert_eq!(best.word, "deadbeef");
+
+        // 3-letter words are kept, they are priced as common ones
+        let lower = "000000000000000000000000000000000000bad0";
+        let bad = dictionary.find_matches(lower, &lower.to_uppercase());
+        assert_eq!(bad.len(), 1);
+        assert_eq!(
+            bad[0].difficulty,
+            dictionary.tail_difficulty(hexspeak_difficulty(3, 38, 1, 3))
+        );
+        // case exact 3-letter word inside is still more common than any 4-letter word inside
+        assert!(bad[0].difficulty < cafe.difficulty);
+    }
+
+    #[test]
+    fn test_prefix_bonus() {
+        let dictionary = HexspeakDictionary::from_words(["c0ffee"]);
+        let prefix = dictionary
+            .best_match(
+                "c0ffee1111111111111111111111111111111111",
+                "C0ffee1111111111111111111111111111111111",
+            )
+            .unwrap();
+        let inside = dictionary
+            .best_match(
+                "1111111111111111c0ffee111111111111111111",
+                "1111111111111111C0ffee111111111111111111",
+            )
+            .unwrap();
+        assert!(prefix.difficulty > inside.difficulty);
+        // 34 inside positions plus prefix itself
+        assert!((prefix.difficulty / inside.difficulty - 35.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use serde::{Deserialize, Serialize};
+use web3::types::Address;
+
+/// Uniswap v4 hook permissions are encoded in the lowest 14 bits of the hook address
+pub const HOOK_FLAGS_MASK: u16 = (1 << 14) - 1;
+
+pub const BEFORE_INITIALIZE_FLAG: u16 = 1 << 13;
+pub const AFTER_INITIALIZE_FLAG: u16 = 1 << 12;
+pub const BEFORE_ADD_LIQUIDITY_FLAG: u16 = 1 << 11;
+pub const AFTER_ADD_LIQUIDITY_FLAG: u16 = 1 << 10;
+pub const BEFORE_REMOVE_LIQUIDITY_FLAG: u16 = 1 << 9;
+pub const AFTER_REMOVE_LIQUIDITY_FLAG: u16 = 1 << 8;
+pub const BEFORE_SWAP_FLAG: u16 = 1 << 7;
+pub const AFTER_SWAP_FLAG: u16 = 1 << 6;
+pub const BEFORE_DONATE_FLAG: u16 = 1 << 5;
+pub const AFTER_DONATE_FLAG: u16 = 1 << 4;
+pub const BEFORE_SWAP_RETURNS_DELTA_FLAG: u16 = 1 << 3;
+pub const AFTER_SWAP_RETURNS_DELTA_FLAG: u16 = 1 << 2;
+pub const AFTER_ADD_LIQUIDITY_RETURNS_DELTA_FLAG: u16 = 1 << 1;
+pub const AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA_FLAG: u16 = 1 << 0;
+
+pub const HOOK_FLAG_NAMES: [(&str, u16); 14] = [
+    ("beforeInitialize", BEFORE_INITIALIZE_FLAG),
+    ("afterInitialize", AFTER_INITIALIZE_FLAG),
+    ("beforeAddLiquidity", BEFORE_ADD_LIQUIDITY_FLAG),
+    ("afterAddLiquidity", AFTER_ADD_LIQUIDITY_FLAG),
+    ("beforeRemoveLiquidity", BEFORE_REMOVE_LIQUIDITY_FLAG),
+    ("afterRemoveLiquidity", AFTER_REMOVE_LIQUIDITY_FLAG),
+    ("beforeSwap", BEFORE_SWAP_FLAG),
+    ("afterSwap", AFTER_SWAP_FLAG),
+    ("beforeDonate", BEFORE_DONATE_FLAG),
+    ("afterDonate", AFTER_DONATE_FLAG),
+    ("beforeSwapReturnsDelta", BEFORE_SWAP_RETURNS_DELTA_FLAG),
+    ("afterSwapReturnsDelta", AFTER_SWAP_RETURNS_DELTA_FLAG),
+    (
+        "afterAddLiquidityReturnsDelta",
+        AFTER_ADD_LIQUIDITY_RETURNS_DELTA_FLAG,
+    ),
+    (
+        "afterRemoveLiquidityReturnsDelta",
+        AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA_FLAG,
+    ),
+];
+
+/// Returns delta flags are only valid together with their base flag (see Hooks.sol)
+const RETURNS_DELTA_DEPENDENCIES: [(u16, u16); 4] = [
+    (BEFORE_SWAP_RETURNS_DELTA_FLAG, BEFORE_SWAP_FLAG),
+    (AFTER_SWAP_RETURNS_DELTA_FLAG, AFTER_SWAP_FLAG),
+    (
+        AFTER_ADD_LIQUIDITY_RETURNS_DELTA_FLAG,
+        AFTER_ADD_LIQUIDITY_FLAG,
+    ),
+    (
+        AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA_FLAG,
+        AFTER_REMOVE_LIQUIDITY_FLAG,
+    ),
+];
+
+pub fn hook_flags(address: &Address) -> u16 {
+    let bytes = address.as_bytes();
+    u16::from_be_bytes([bytes[18], bytes[19]]) & HOOK_FLAGS_MASK
+}
+
+/// Human readable list of permissions, like "beforeSwap|afterSwap"
+pub fn describe_hook_flags(flags: u16) -> String {
+    let names = HOOK_FLAG_NAMES
+        .iter()
+        .filter(|(_, flag)| flags & flag != 0)
+        .map(|(name, _)| *name)
+        .collect::<Vec<&str>>();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join("|")
+    }
+}
+
+/// Same rules as Hooks.isValidHookAddress for hook with static fee
+pub fn is_valid_hook_flags(flags: u16) -> bool {
+    if flags & HOOK_FLAGS_MASK == 0 {
+        return false;
+    }
+    RETURNS_DELTA_DEPENDENCIES
+        .iter()
+        .all(|(delta_flag, base_flag)| flags & delta_flag == 0 || flags & base_flag != 0)
+}
+
+/// Required bits have to be set and forbidden bits have to be cleared, other bits are free
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HookFlagsTarget {
+    pub required: u16,
+    pub forbidden: u16,
+}
+
+impl HookFlagsTarget {
+    pub fn new(required: u16, forbidden: u16) -> Result<Self, AddressologyError> {
+        let target = Self {
+            required,
+            forbidden,
+        };
+        target.validate()?;
+        Ok(target)
+    }
+
+    /// Hook address with exactly given permissions
+    pub fn exact(flags: u16) -> Result<Self, AddressologyError> {
+        Self::new(flags, !flags & HOOK_FLAGS_MASK)
+    }
+
+    pub fn validate(&self) -> Result<(), AddressologyError> {
+        if (self.required | self.forbidden) & !HOOK_FLAGS_MASK != 0 {
+            return Err(err_custom_create!(
+                "Hook flags have to fit in lowest 14 bits (required: {:#06x}, forbidden: {:#06x})",
+                self.required,
+                self.forbidden
+            ));
+        }
+        if self.required & self.forbidden != 0 {
+            return Err(err_custom_create!(
+                "Hook flags {} cannot be both required and forbidden",
+                describe_hook_flags(self.required & self.forbidden)
+            ));
+        }
+        if self.matching_flags_count() == 0 {
+            return Err(err_custom_create!(
+                "No valid hook address matches required: {}, forbidden: {}",
+                describe_hook_flags(self.required),
+                describe_hook_flags(self.forbidden)
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn matches_flags(&self, flags: u16) -> bool {
+        flags & self.required == self.required
+            && flags & self.forbidden == 0
+            && is_valid_hook_flags(flags)
+    }
+
+    pub fn matches(&self, address: &Address) -> bool {
+        self.matches_flags(hook_flags(address))
+    }
+
+    fn matching_flags_count(&self) -> u32 {
+        (0..=HOOK_FLAGS_MASK)
+            .filter(|flags| self.matches_flags(*flags))
+            .count() as u32
+    }
+
+    /// Expected number of addresses to check to find a match (low bits are uniform)
+    pub fn difficulty(&self) -> f64 {
+        let matching = self.matching_flags_count();
+        if matching == 0 {
+            return f64::INFINITY;
+        }
+        (HOOK_FLAGS_MASK as f64 + 1.0) / matching as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_hook_flags() {
+        // before swap + after swap
+        let address = Address::from_str("0x00000000000000000000000000000000000000C0").unwrap();
+        assert_eq!(hook_flags(&address), BEFORE_SWAP_FLAG | AFTER_SWAP_FLAG);
+
+        // bits above lowest 14 are ignored
+        let address = Address::from_str("0xffffffffffffffffffffffffffffffffffffc080").unwrap();
+        assert_eq!(hook_flags(&address), BEFORE_SWAP_FLAG);
+
+        assert_eq!(describe_hook_flags(0xC0), "beforeSwap|afterSwap");
+
+        let exact = HookFlagsTarget::exact(BEFORE_SWAP_FLAG).unwrap();
+        assert!(exact.matches(&address));
+        assert_eq!(exact.difficulty(), 16384.0);
+
+        let target = HookFlagsTarget::new(BEFORE_SWAP_FLAG, AFTER_SWAP_FLAG).unwrap();
+        assert!(target.matches(&address));
+        assert!(!target
+            .matches(&Address::from_str("0x00000000000000000000000000000000000000C0").unwrap()));
+    }
+
+    #[test]
+    fn test_hook_flags_validation() {
+        assert!(HookFlagsTarget::new(1 << 14, 0).is_err());
+        assert!(HookFlagsTarget::new(BEFORE_SWAP_FLAG, BEFORE_SWAP_FLAG).is_err());
+        // returns delta without base flag can never be deployed
+        assert!(HookFlagsTarget::exact(BEFORE_SWAP_RETURNS_DELTA_FLAG).is_err());
+        assert!(HookFlagsTarget::exact(BEFORE_SWAP_RETURNS_DELTA_FLAG | BEFORE_SWAP_FLAG).is_ok());
+        assert!(!is_valid_hook_flags(0));
+    }
+
+    #[test]
+    fn test_hook_flags_difficulty() {
+        // only one bit is constrained, but returns delta dependencies and empty flags
+        // make some of the remaining combinations invalid
+        let target = HookFlagsTarget::new(BEFORE_INITIALIZE_FLAG, 0).unwrap();
+        let brute = (0..=HOOK_FLAGS_MASK)
+            .filter(|flags| flags & BEFORE_INITIALIZE_FLAG != 0 && is_valid_hook_flags(*flags))
+            .count();
+        // 2^13 combinations of other bits, each dependency pair allows 3 out of 4 combinations
+        assert_eq!(brute, (1 << 5) * 81);
+        assert_eq!(target.difficulty(), 16384.0 / brute as f64);
+    }
+}
@@ -2,11 +2,26 @@ use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use web3::signing::keccak256;
 use web3::types::H160;
+mod calibration;
+mod estimate;
 #[allow(clippy::module_inception)]
 mod fancy;
+mod fast_score;
+mod hexspeak;
+mod hook;
+mod pricing;
 mod score;
+mod verify;
 use crate::types::DbAddress;
+pub use calibration::*;
+pub use estimate::*;
+pub use fancy::*;
+pub use fast_score::*;
+pub use hexspeak::*;
+pub use hook::*;
+pub use pricing::*;
 pub use score::*;
+pub use verify::*;
 
 #[derive(Serialize, Deserialize, sqlx::FromRow, PartialEq, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -14,7 +29,22 @@ pub struct FancyDbObjMin {
     pub address: DbAddress,
     pub salt: String,
     pub factory: Option<DbAddress>,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub init_code_hash: Option<String>,
     pub public_key_base: Option<String>,
+    /// CREATE3 salt derivation scheme, None when the factory default applies
+    #[serde(default)]
+    #[sqlx(default)]
+    pub create3_scheme: Option<String>,
+    /// Deployer address for schemes that mix it into the salt
+    #[serde(default)]
+    #[sqlx(default)]
+    pub caller: Option<DbAddress>,
+    /// Chain id for CreateX salts with cross-chain redeploy protection
+    #[serde(default)]
+    #[sqlx(default)]
+    pub chain_id: Option<i64>,
 }
 
 #[derive(Serialize, Deserialize, sqlx::FromRow, PartialEq, Debug, Clone)]
@@ -23,7 +53,22 @@ pub struct FancyDbObj {
     pub address: DbAddress,
     pub salt: String,
     pub factory: Option<DbAddress>,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub init_code_hash: Option<String>,
     pub public_key_base: Option<String>,
+    /// CREATE3 salt derivation scheme, None when the factory default applies
+    #[serde(default)]
+    #[sqlx(default)]
+    pub create3_scheme: Option<String>,
+    /// Deployer address for schemes that mix it into the salt
+    #[serde(default)]
+    #[sqlx(default)]
+    pub caller: Option<DbAddress>,
+    /// Chain id for CreateX salts with cross-chain redeploy protection
+    #[serde(default)]
+    #[sqlx(default)]
+    pub chain_id: Option<i64>,
     pub created: NaiveDateTime,
     pub score: f64,
     pub owner: Option<String>,
@@ -0,0 +1,310 @@
+use crate::config::{get_base_difficulty, get_base_difficulty_price, get_config_opt};
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use crate::fancy::{FancyScore, FancyScoreCategory};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static PRICING: OnceLock<Pricing> = OnceLock::new();
+
+/// Maps difficulty of the address to multiplier of base price
+pub trait PricingModel: Send + Sync {
+    fn price_multiplier(&self, difficulty: f64, base_difficulty: f64) -> f64;
+}
+
+/// Price grows with difficulty, addresses easier than base difficulty cost base price
+pub struct LinearPricing;
+
+impl PricingModel for LinearPricing {
+    fn price_multiplier(&self, difficulty: f64, base_difficulty: f64) -> f64 {
+        if difficulty <= base_difficulty {
+            1.0
+        } else {
+            difficulty / base_difficulty
+        }
+    }
+}
+
+/// Every doubling of difficulty above base adds factor to the multiplier
+pub struct LogarithmicPricing {
+    pub factor: f64,
+}
+
+impl PricingModel for LogarithmicPricing {
+    fn price_multiplier(&self, difficulty: f64, base_difficulty: f64) -> f64 {
+        if difficulty <= base_difficulty {
+            1.0
+        } else {
+            1.0 + self.factor * (difficulty / base_difficulty).log2()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct PriceTier {
+    /// Tier applies from this multiple of base difficulty
+    pub min_ratio: f64,
+    pub multiplier: f64,
+}
+
+/// Fixed multiplier of the highest tier reached, base price below the first tier
+pub struct TieredPricing {
+    pub tiers: Vec<PriceTier>,
+}
+
+impl PricingModel for TieredPricing {
+    fn price_multiplier(&self, difficulty: f64, base_difficulty: f64) -> f64 {
+        let ratio = difficulty / base_difficulty;
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| ratio >= tier.min_ratio)
+            .map(|tier| tier.multiplier)
+            .unwrap_or(1.0)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum PricingModelConfig {
+    #[default]
+    Linear,
+    Logarithmic {
+        factor: f64,
+    },
+    Tiered {
+        tiers: Vec<PriceTier>,
+    },
+}
+
+impl PricingModelConfig {
+    pub fn validate(&self) -> Result<(), AddressologyError> {
+        match self {
+            PricingModelConfig::Linear => Ok(()),
+            PricingModelConfig::Logarithmic { factor } => {
+                if !factor.is_finite() || *factor <= 0.0 {
+                    return Err(err_custom_create!(
+                        "Logarithmic pricing factor has to be positive, got {factor}"
+                    ));
+                }
+                Ok(())
+            }
+            PricingModelConfig::Tiered { tiers } => {
+                if tiers.is_empty() {
+                    return Err(err_custom_create!("Tiered pricing needs at least one tier"));
+                }
+                for tier in tiers {
+                    if !tier.min_ratio.is_finite() || tier.min_ratio <= 0.0 {
+                        return Err(err_custom_create!(
+                            "Tier min-ratio has to be positive, got {}",
+                            tier.min_ratio
+                        ));
+                    }
+                    if !tier.multiplier.is_finite() || tier.multiplier < 1.0 {
+                        return Err(err_custom_create!(
+                            "Tier multiplier has to be at least 1, got {}",
+                            tier.multiplier
+                        ));
+                    }
+                }
+                if tiers
+                    .windows(2)
+                    .any(|w| w[0].min_ratio >= w[1].min_ratio || w[0].multiplier > w[1].multiplier)
+                {
+                    return Err(err_custom_create!(
+                        "Tiers have to be sorted by min-ratio and multipliers cannot decrease"
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn build(&self) -> Box<dyn PricingModel> {
+        match self {
+            PricingModelConfig::Linear => Box::new(LinearPricing),
+            PricingModelConfig::Logarithmic { factor } => {
+                Box::new(LogarithmicPricing { factor: *factor })
+            }
+            PricingModelConfig::Tiered { tiers } => Box::new(TieredPricing {
+                tiers: tiers.clone(),
+            }),
+        }
+    }
+}
+
+/// Pricing section of config.toml, missing base values are taken from
+/// BASE_DIFFICULTY and BASE_DIFFICULTY_PRICE env
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct PricingConfig {
+    #[serde(default)]
+    pub model: PricingModelConfig,
+    #[serde(default)]
+    pub base_difficulty: Option<f64>,
+    #[serde(default)]
+    pub base_price: Option<i64>,
+    /// Model used instead of default one for given category key (like leading_zeroes)
+    #[serde(default)]
+    pub category_overrides: BTreeMap<String, PricingModelConfig>,
+}
+
+pub struct Pricing {
+    model: Box<dyn PricingModel>,
+    category_models: BTreeMap<String, Box<dyn PricingModel>>,
+    pub base_difficulty: f64,
+    pub base_price: i64,
+}
+
+impl Pricing {
+    pub fn from_config(config: &PricingConfig) -> Result<Self, AddressologyError> {
+        let base_difficulty = config.base_difficulty.unwrap_or_else(get_base_difficulty);
+        if !base_difficulty.is_finite() || base_difficulty < 1.0 {
+            return Err(err_custom_create!(
+                "Base difficulty has to be at least 1, got {base_difficulty}"
+            ));
+        }
+        let base_price = config.base_price.unwrap_or_else(get_base_difficulty_price);
+        if base_price < 0 {
+            return Err(err_custom_create!(
+                "Base price cannot be negative, got {base_price}"
+            ));
+        }
+        config.model.validate()?;
+        let mut category_models = BTreeMap::new();
+        for (category, model) in &config.category_overrides {
+            if FancyScoreCategory::from_str(category).is_err() {
+                return Err(err_custom_create!(
+                    "Unknown category {category} in pricing overrides"
+                ));
+            }
+            model.validate()?;
+            category_models.insert(category.clone(), model.build());
+        }
+        Ok(Self {
+            model: config.model.build(),
+            category_models,
+            base_difficulty,
+            base_price,
+        })
+    }
+
+    pub fn price_multiplier(&self, category: &str, difficulty: f64) -> f64 {
+        self.category_models
+            .get(category)
+            .unwrap_or(&self.model)
+            .price_multiplier(difficulty, self.base_difficulty)
+    }
+
+    pub fn price(&self, score: &FancyScore) -> i64 {
+        (self.price_multiplier(&score.category, score.total_score) * self.base_price as f64) as i64
+    }
+}
+
+/// Pricing from ApplicationConfig, linear pricing when config is not initialized
+pub fn get_pricing() -> &'static Pricing {
+    PRICING.get_or_init(|| {
+        let config = get_config_opt()
+            .map(|config| config.pricing.clone())
+            .unwrap_or_default();
+        Pricing::from_config(&config).expect("Pricing config is validated when config is loaded")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pricing_models() {
+        let base = 16.0f64.powi(9);
+        let config = PricingConfig {
+            model: PricingModelConfig::Logarithmic { factor: 2.0 },
+            base_difficulty: Some(base),
+            base_price: Some(1000),
+            category_overrides: BTreeMap::from([(
+                "hexspeak".to_string(),
+                PricingModelConfig::Tiered {
+                    tiers: vec![
+                        PriceTier {
+                            min_ratio: 1.0,
+                            multiplier: 2.0,
+                        },
+                        PriceTier {
+                            min_ratio: 256.0,
+                            multiplier: 10.0,
+                        },
+                    ],
+                },
+            )]),
+        };
+        let pricing = Pricing::from_config(&config).unwrap();
+        assert_eq!(pricing.price_multiplier("leading_zeroes", base / 2.0), 1.0);
+        assert_eq!(pricing.price_multiplier("leading_zeroes", base * 16.0), 9.0);
+        assert_eq!(pricing.price_multiplier("hexspeak", base / 2.0), 1.0);
+        assert_eq!(pricing.price_multiplier("hexspeak", base * 16.0), 2.0);
+        assert_eq!(pricing.price_multiplier("hexspeak", base * 256.0), 10.0);
+
+        let linear = Pricing::from_config(&PricingConfig {
+            base_difficulty: Some(base),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(linear.price_multiplier("leading_zeroes", base * 16.0), 16.0);
+    }
+
+    #[test]
+    fn test_pricing_validation() {
+        let invalid = [
+            PricingConfig {
+                base_difficulty: Some(0.0),
+                ..Default::default()
+            },
+            PricingConfig {
+                base_price: Some(-1),
+                ..Default::default()
+            },
+            PricingConfig {
+                model: PricingModelConfig::Logarithmic { factor: f64::NAN },
+                ..Default::default()
+            },
+            PricingConfig {
+                model: PricingModelConfig::Tiered { tiers: vec![] },
+                ..Default::default()
+            },
+            PricingConfig {
+                category_overrides: BTreeMap::from([(
+                    "no_such_category".to_string(),
+                    PricingModelConfig::Linear,
+                )]),
+                ..Default::default()
+            },
+        ];
+        for config in invalid {
+            assert!(Pricing::from_config(&config).is_err(), "{config:?}");
+        }
+
+        let config: PricingConfig = toml::from_str(
+            r#"
+            base-price = 500
+            [model]
+            type = "tiered"
+            tiers = [{ min-ratio = 1.0, multiplier = 1.5 }, { min-ratio = 0.5, multiplier = 2.0 }]
+            "#,
+        )
+        .unwrap();
+        assert!(Pricing::from_config(&config).is_err());
+
+        // pricing section has to survive saving and loading of config.toml
+        let mut app_config = crate::config::ApplicationConfig::default();
+        app_config.pricing.model = PricingModelConfig::Logarithmic { factor: 1.5 };
+        app_config.pricing.category_overrides =
+            BTreeMap::from([("zero_bytes".to_string(), PricingModelConfig::Linear)]);
+        let saved = toml::to_string(&app_config).unwrap();
+        let loaded: crate::config::ApplicationConfig = toml::from_str(&saved).unwrap();
+        assert_eq!(loaded.pricing, app_config.pricing);
+    }
+}
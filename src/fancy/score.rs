@@ -1,7 +1,6 @@
-use crate::config::get_base_difficulty;
 use std::collections::BTreeMap;
 
-use crate::fancy::address_to_mixed_case;
+use crate::fancy::{address_to_mixed_case, get_hexspeak_dictionary, get_pricing, score_fast};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -40,6 +39,11 @@ pub enum FancyScoreCategory {
     SnakeScoreNoCase,
     SnakeScoreNeedCase,
     SnakeScoreNeedLetters,
+    ZeroBytes,
+    Hexspeak,
+    PeriodicRepeat,
+    SequenceRun,
+    Palindrome,
 
     LeadingLetters,
     PatternScore,
@@ -59,6 +63,11 @@ impl Display for FancyScoreCategory {
             FancyScoreCategory::SnakeScoreNoCase => write!(f, "snake_score_no_case"),
             FancyScoreCategory::SnakeScoreNeedCase => write!(f, "snake_score_need_case"),
             FancyScoreCategory::SnakeScoreNeedLetters => write!(f, "snake_score_need_letters"),
+            FancyScoreCategory::ZeroBytes => write!(f, "zero_bytes"),
+            FancyScoreCategory::Hexspeak => write!(f, "hexspeak"),
+            FancyScoreCategory::PeriodicRepeat => write!(f, "periodic_repeat"),
+            FancyScoreCategory::SequenceRun => write!(f, "sequence_run"),
+            FancyScoreCategory::Palindrome => write!(f, "palindrome"),
             FancyScoreCategory::LeadingLetters => write!(f, "leading_letters"),
             FancyScoreCategory::PatternScore => write!(f, "pattern_score"),
             FancyScoreCategory::Random => write!(f, "random"),
@@ -80,6 +89,11 @@ impl FromStr for FancyScoreCategory {
             "snake_score_no_case" => Ok(FancyScoreCategory::SnakeScoreNoCase),
             "snake_score_need_case" => Ok(FancyScoreCategory::SnakeScoreNeedCase),
             "snake_score_need_letters" => Ok(FancyScoreCategory::SnakeScoreNeedLetters),
+            "zero_bytes" => Ok(FancyScoreCategory::ZeroBytes),
+            "hexspeak" => Ok(FancyScoreCategory::Hexspeak),
+            "periodic_repeat" => Ok(FancyScoreCategory::PeriodicRepeat),
+            "sequence_run" => Ok(FancyScoreCategory::SequenceRun),
+            "palindrome" => Ok(FancyScoreCategory::Palindrome),
             "leading_letters" => Ok(FancyScoreCategory::LeadingLetters),
             "pattern_score" => Ok(FancyScoreCategory::PatternScore),
             "random" => Ok(FancyScoreCategory::Random),
@@ -154,6 +168,36 @@ pub fn list_score_categories() -> Vec<FancyCategoryInfo> {
                 name: "Snake Score with Letters".to_string(),
                 description: "The number of repeating letters in the address.".to_string(),
             }),
+            FancyScoreCategory::ZeroBytes => categories.push(FancyCategoryInfo {
+                key: category.to_string(),
+                name: "Zero Bytes".to_string(),
+                description: "The number of zero bytes anywhere in the address (cheaper calldata)."
+                    .to_string(),
+            }),
+            FancyScoreCategory::Hexspeak => categories.push(FancyCategoryInfo {
+                key: category.to_string(),
+                name: "Hexspeak".to_string(),
+                description: "Readable word from dictionary (like c0ffee), longest at the start."
+                    .to_string(),
+            }),
+            FancyScoreCategory::PeriodicRepeat => categories.push(FancyCategoryInfo {
+                key: category.to_string(),
+                name: "Periodic Repeat".to_string(),
+                description: "Length of the segment repeating with period 2 to 8 (like abcabcabc)."
+                    .to_string(),
+            }),
+            FancyScoreCategory::SequenceRun => categories.push(FancyCategoryInfo {
+                key: category.to_string(),
+                name: "Sequence Run".to_string(),
+                description: "Length of ascending or descending run of characters (like 0123456)."
+                    .to_string(),
+            }),
+            FancyScoreCategory::Palindrome => categories.push(FancyCategoryInfo {
+                key: category.to_string(),
+                name: "Palindrome".to_string(),
+                description: "Length of the longest mirrored segment, 40 for full address."
+                    .to_string(),
+            }),
             FancyScoreCategory::LeadingLetters => categories.push(FancyCategoryInfo {
                 key: category.to_string(),
                 name: "Leading Letters".to_string(),
@@ -225,6 +269,234 @@ pub fn snake_difficulty(snake: i64, total: u64) -> f64 {
     total_combinations(total as f64) / combinations_total
 }
 
+// Tail of case sensitive snake: pairs of equal neighbours in mixed case address.
+// Case of letters comes from address hash, so character is one of 10 digits (1/16 each)
+// or 12 cased letters (1/32 each). Neighbouring pairs share a character and are not
+// independent, so distribution is computed over kind (digit, letter) of previous character
+fn mixed_snake_tail(pairs: i64, total: u64, letters_only: bool) -> f64 {
+    if pairs <= 0 {
+        return 1.0f64;
+    }
+    let len = total as usize;
+    // counts[kind][n] - probability of n counted pairs so far, kind 0 - digit, 1 - letter
+    let mut counts = vec![vec![0.0f64; len]; 2];
+    counts[0][0] = 10.0 / 16.0;
+    counts[1][0] = 6.0 / 16.0;
+    for _ in 1..total {
+        let mut next = vec![vec![0.0f64; len]; 2];
+        for n in 0..len - 1 {
+            let (digit, letter) = (counts[0][n], counts[1][n]);
+            // same digit, other digit, any letter
+            next[0][if letters_only { n } else { n + 1 }] += digit / 16.0;
+            next[0][n] += digit * 9.0 / 16.0;
+            next[1][n] += digit * 6.0 / 16.0;
+            // same letter in same case, any digit, other letter or case
+            next[1][n + 1] += letter / 32.0;
+            next[0][n] += letter * 10.0 / 16.0;
+            next[1][n] += letter * 11.0 / 32.0;
+        }
+        counts = next;
+    }
+    (pairs as usize..len)
+        .map(|n| counts[0][n] + counts[1][n])
+        .sum()
+}
+
+pub fn snake_need_case_difficulty(pairs: i64, total: u64) -> f64 {
+    1.0 / mixed_snake_tail(pairs, total, false)
+}
+
+pub fn snake_need_letters_difficulty(letter_pairs: i64, total: u64) -> f64 {
+    1.0 / mixed_snake_tail(letter_pairs, total, true)
+}
+
+// number of 20 byte addresses with exactly zero_bytes bytes equal to 0x00
+pub fn zero_bytes_combinations(zero_bytes: u64, total: u64) -> f64 {
+    if zero_bytes > total {
+        return 0.0f64;
+    }
+    combinations(total as i64, zero_bytes as i64) * 255.0f64.powf((total - zero_bytes) as f64)
+}
+
+// inverse of binomial tail probability of getting at least zero_bytes zero bytes
+pub fn zero_bytes_difficulty(zero_bytes: u64, total: u64) -> f64 {
+    let mut combinations_total = 0.0f64;
+    for i in zero_bytes..=total {
+        combinations_total += zero_bytes_combinations(i, total);
+    }
+    256.0f64.powf(total as f64) / combinations_total
+}
+
+// probability of at least one run of `run` successes in `trials` independent trials
+pub fn longest_run_probability(run: u64, trials: u64, chance: f64) -> f64 {
+    if run == 0 {
+        return 1.0f64;
+    }
+    if run > trials {
+        return 0.0f64;
+    }
+    // states[j] - probability of current run of length j without reaching `run` yet
+    let mut states = vec![0.0f64; run as usize];
+    states[0] = 1.0f64;
+    let mut reached = 0.0f64;
+    for _ in 0..trials {
+        let mut next_states = vec![0.0f64; run as usize];
+        next_states[0] = states.iter().sum::<f64>() * (1.0 - chance);
+        for j in 0..run as usize - 1 {
+            next_states[j + 1] = states[j] * chance;
+        }
+        reached += states[run as usize - 1] * chance;
+        states = next_states;
+    }
+    reached
+}
+
+// s[i] == s[i - period] are independent events with chance 1/16 (differences are uniform),
+// so every period is exact, periods are summed (union bound). Constant segments are left
+// to snake score, which only makes the estimate more conservative
+pub fn periodic_difficulty(length: u64, total: u64) -> f64 {
+    if length == 0 {
+        return 1.0f64;
+    }
+    let mut probability = 0.0f64;
+    for period in 2..=8u64 {
+        if 2 * period > total {
+            continue;
+        }
+        let run = length.saturating_sub(period).max(period);
+        probability += longest_run_probability(run, total - period, 1.0 / 16.0);
+    }
+    (1.0 / probability).max(1.0f64)
+}
+
+// probability of at least `run` consecutive steps of +1 or -1 (mod 16) in `trials` steps
+pub fn sequence_run_probability(run: u64, trials: u64) -> f64 {
+    if run == 0 {
+        return 1.0f64;
+    }
+    if run > trials {
+        return 0.0f64;
+    }
+    let chance = 1.0f64 / 16.0;
+    let run = run as usize;
+    // ascending[j], descending[j] - probability of current run of j steps in given direction
+    let mut ascending = vec![0.0f64; run];
+    let mut descending = vec![0.0f64; run];
+    let mut no_run = 1.0f64;
+    let mut reached = 0.0f64;
+    for _ in 0..trials {
+        let mut next_ascending = vec![0.0f64; run];
+        let mut next_descending = vec![0.0f64; run];
+        let sum_ascending = ascending.iter().sum::<f64>();
+        let sum_descending = descending.iter().sum::<f64>();
+        let next_no_run = (no_run + sum_ascending + sum_descending) * (1.0 - 2.0 * chance);
+
+        for (current, next, start_from) in [
+            (&ascending, &mut next_ascending, no_run + sum_descending),
+            (&descending, &mut next_descending, no_run + sum_ascending),
+        ] {
+            if run == 1 {
+                reached += start_from * chance;
+            } else {
+                next[1] += start_from * chance;
+            }
+            for j in 1..run {
+                if j + 1 < run {
+                    next[j + 1] += current[j] * chance;
+                } else {
+                    reached += current[j] * chance;
+                }
+            }
+        }
+        ascending = next_ascending;
+        descending = next_descending;
+        no_run = next_no_run;
+    }
+    reached
+}
+
+pub fn sequence_difficulty(length: u64, total: u64) -> f64 {
+    if length <= 1 {
+        return 1.0f64;
+    }
+    (1.0 / sequence_run_probability(length - 1, total - 1)).max(1.0f64)
+}
+
+// longest palindrome at least `length` means palindrome of length `length` or `length + 1`
+// somewhere, estimated by union bound over positions (exact for full address)
+pub fn palindrome_difficulty(length: u64, total: u64) -> f64 {
+    if length <= 1 {
+        return 1.0f64;
+    }
+    let probability = (total + 1 - length) as f64 * 16.0f64.powf(-((length / 2) as f64))
+        + (total - length) as f64 * 16.0f64.powf(-(length.div_ceil(2) as f64));
+    (1.0 / probability).max(1.0f64)
+}
+
+/// Longest segment repeating with period 2 to 8, at least two periods long.
+/// Constant segments (like 0000) are snakes, they are not counted as periodic
+pub fn longest_periodic_segment(nibbles: &[u8]) -> u64 {
+    let mut longest = 0;
+    for period in 2..=8usize {
+        let mut run = 0;
+        // last index i where nibbles[i] != nibbles[i - 1]
+        let mut last_change = 0;
+        for i in 1..nibbles.len() {
+            if nibbles[i] != nibbles[i - 1] {
+                last_change = i;
+            }
+            if i < period {
+                continue;
+            }
+            if nibbles[i] == nibbles[i - period] {
+                run += 1;
+                // segment starts at i + 1 - run - period
+                if run >= period && last_change > i + 1 - run - period {
+                    longest = longest.max(run + period);
+                }
+            } else {
+                run = 0;
+            }
+        }
+    }
+    longest as u64
+}
+
+pub fn longest_sequence_run(nibbles: &[u8]) -> u64 {
+    let mut longest = 1;
+    let mut run = 1;
+    let mut prev_step = 0;
+    for (c, prev) in nibbles.iter().skip(1).zip(nibbles.iter()) {
+        let step = c.wrapping_sub(*prev) & 0xf;
+        if step == 1 || step == 15 {
+            run = if step == prev_step { run + 1 } else { 2 };
+            longest = longest.max(run);
+        } else {
+            run = 1;
+        }
+        prev_step = step;
+    }
+    longest.min(nibbles.len() as u64)
+}
+
+pub fn longest_palindrome(nibbles: &[u8]) -> u64 {
+    let mut longest = nibbles.len().min(1);
+    for center in 0..nibbles.len() {
+        // odd (left == right) and even (right == left + 1) palindromes
+        for (mut left, mut right) in [(center, center), (center, center + 1)] {
+            while right < nibbles.len() && nibbles[left] == nibbles[right] {
+                longest = longest.max(right - left + 1);
+                if left == 0 {
+                    break;
+                }
+                left -= 1;
+                right += 1;
+            }
+        }
+    }
+    longest as u64
+}
+
 #[tokio::test]
 async fn tx_test() {
     assert_eq!(combinations(40, 1), 40.0);
@@ -260,9 +532,14 @@ async fn tx_test() {
     );
 }
 
+pub fn score_fancy(address: Address) -> FancyScore {
+    score_fast(&address).to_fancy_score(address)
+}
+
+/// String based scoring, kept as readable specification that score_fast has to match
 #[allow(unused)]
 #[allow(clippy::vec_init_then_push)]
-pub fn score_fancy(address: Address) -> FancyScore {
+pub fn score_fancy_reference(address: Address) -> FancyScore {
     let mut score = FancyScore::default();
 
     score.address_lower_case = format!("{:#x}", address).to_lowercase();
@@ -308,15 +585,15 @@ pub fn score_fancy(address: Address) -> FancyScore {
         }
     }
 
-    let mut extra_letter_bonus = 1;
+    let mut snake_letter_pairs = 0;
     let mut snake_score_mixed = 0;
     let first_char = mixed_address_str.chars().next().unwrap();
     let mut prev_char = first_char;
-    for c in mixed_address_str.chars() {
+    for (i, c) in mixed_address_str.chars().enumerate() {
         if c == prev_char {
             snake_score_mixed += 1;
-            if c.is_alphabetic() {
-                extra_letter_bonus += 1;
+            if i > 0 && c.is_alphabetic() {
+                snake_letter_pairs += 1;
             }
         } else {
             prev_char = c;
@@ -356,6 +633,8 @@ pub fn score_fancy(address: Address) -> FancyScore {
         }
     }
 
+    let zero_bytes = address.as_bytes().iter().filter(|b| **b == 0).count() as u64;
+
     let mut snake_score_no_case: i64 = 0;
     let mut prev_char = address_str.chars().next().unwrap();
     for c in address_str.chars() {
@@ -511,13 +790,55 @@ pub fn score_fancy(address: Address) -> FancyScore {
     score_entries.push(FancyScoreEntry {
         category: FancyScoreCategory::SnakeScoreNeedCase,
         score: (snake_score_no_case - 1) as f64,
-        difficulty: 5.0f64 * snake_difficulty(snake_score_mixed - 1, 40),
+        difficulty: snake_need_case_difficulty(snake_score_mixed - 1, 40),
     });
 
     score_entries.push(FancyScoreEntry {
         category: FancyScoreCategory::SnakeScoreNeedLetters,
         score: (snake_score_no_case - 1) as f64,
-        difficulty: (extra_letter_bonus as f64) * snake_difficulty(snake_score_no_case - 1, 40),
+        difficulty: snake_need_letters_difficulty(snake_letter_pairs, 40),
+    });
+
+    score_entries.push(FancyScoreEntry {
+        category: FancyScoreCategory::ZeroBytes,
+        score: zero_bytes as f64,
+        difficulty: zero_bytes_difficulty(zero_bytes, 20),
+    });
+
+    let hexspeak_match = get_hexspeak_dictionary().best_match(address_str, mixed_address_str);
+    score_entries.push(FancyScoreEntry {
+        category: FancyScoreCategory::Hexspeak,
+        score: hexspeak_match
+            .as_ref()
+            .map(|m| m.pattern.len() as f64)
+            .unwrap_or(0.0),
+        difficulty: hexspeak_match.map(|m| m.difficulty).unwrap_or(1.0),
+    });
+
+    let nibbles = address_str
+        .chars()
+        .map(|c| c.to_digit(16).unwrap() as u8)
+        .collect::<Vec<u8>>();
+
+    let periodic_segment = longest_periodic_segment(&nibbles);
+    score_entries.push(FancyScoreEntry {
+        category: FancyScoreCategory::PeriodicRepeat,
+        score: periodic_segment as f64,
+        difficulty: periodic_difficulty(periodic_segment, 40),
+    });
+
+    let sequence_run = longest_sequence_run(&nibbles);
+    score_entries.push(FancyScoreEntry {
+        category: FancyScoreCategory::SequenceRun,
+        score: sequence_run as f64,
+        difficulty: sequence_difficulty(sequence_run, 40),
+    });
+
+    let palindrome = longest_palindrome(&nibbles);
+    score_entries.push(FancyScoreEntry {
+        category: FancyScoreCategory::Palindrome,
+        score: palindrome as f64,
+        difficulty: palindrome_difficulty(palindrome, 40),
     });
 
     score_entries.push(FancyScoreEntry {
@@ -559,8 +880,6 @@ pub fn score_fancy(address: Address) -> FancyScore {
         .map(|entry| (entry.category.to_string(), entry.clone()))
         .collect();
 
-    let neutral_price_point = get_base_difficulty();
-
     // This simple method is better than iterator, because of float NaN issues
     let mut biggest_score = score_entries[0].clone();
     for entry in score_entries.iter() {
@@ -571,11 +890,10 @@ pub fn score_fancy(address: Address) -> FancyScore {
 
     let biggest_score_difficulty = biggest_score.difficulty;
 
-    let price_multiplier = if biggest_score_difficulty <= neutral_price_point {
-        1.0
-    } else {
-        biggest_score_difficulty / neutral_price_point
-    };
+    let price_multiplier = get_pricing().price_multiplier(
+        &biggest_score.category.to_string(),
+        biggest_score_difficulty,
+    );
 
     score.total_score = biggest_score_difficulty;
     score.price_multiplier = price_multiplier;
@@ -583,11 +901,112 @@ pub fn score_fancy(address: Address) -> FancyScore {
     score
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreExplanationEntry {
+    pub category: String,
+    pub name: String,
+    pub description: String,
+    pub score: f64,
+    pub difficulty: f64,
+    pub winning: bool,
+}
+
+/// Score breakdown of the address with price, entries are sorted from the most difficult
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreExplanation {
+    pub address: String,
+    pub category: String,
+    pub category_name: String,
+    pub total_score: f64,
+    pub price_multiplier: f64,
+    pub base_difficulty: f64,
+    pub base_price: i64,
+    pub price: i64,
+    pub explanation: String,
+    pub entries: Vec<ScoreExplanationEntry>,
+}
+
+pub fn explain_score(address: Address) -> ScoreExplanation {
+    let score = score_fancy(address);
+    let categories = list_score_categories();
+    let category_info = |key: &str| {
+        categories
+            .iter()
+            .find(|info| info.key == key)
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let mut entries = score
+        .scores
+        .values()
+        .map(|entry| {
+            let key = entry.category.to_string();
+            let info = category_info(&key);
+            ScoreExplanationEntry {
+                winning: key == score.category,
+                category: key,
+                name: info.name,
+                description: info.description,
+                score: entry.score,
+                difficulty: entry.difficulty,
+            }
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| b.difficulty.total_cmp(&a.difficulty));
+
+    let pricing = get_pricing();
+    let category_name = category_info(&score.category).name;
+    let explanation = format!(
+        "{} is the rarest feature, about 1 in {:.0} addresses has it. \
+         With base difficulty {:.0} it is priced at {:.2}x base price.",
+        category_name, score.total_score, pricing.base_difficulty, score.price_multiplier
+    );
+
+    ScoreExplanation {
+        address: score.address_mixed_case.clone(),
+        category: score.category.clone(),
+        category_name,
+        total_score: score.total_score,
+        price_multiplier: score.price_multiplier,
+        base_difficulty: pricing.base_difficulty,
+        base_price: pricing.base_price,
+        price: pricing.price(&score),
+        explanation,
+        entries,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{Rng, SeedableRng};
     use web3::types::Address;
 
+    #[test]
+    fn test_explain_score() {
+        let address = Address::from_str("0x00000000003a6f1c9b2e7d4085f3c6a9e1b74d28").unwrap();
+        let explanation = explain_score(address);
+        let score = score_fancy(address);
+        assert_eq!(explanation.category, "leading_zeroes");
+        assert_eq!(explanation.category_name, "Leading Zeroes");
+        assert_eq!(explanation.entries.len(), score.scores.len());
+        assert!(explanation.entries[0].winning);
+        assert_eq!(explanation.entries[0].category, explanation.category);
+        assert_eq!(explanation.entries.iter().filter(|e| e.winning).count(), 1);
+        assert!(explanation
+            .entries
+            .windows(2)
+            .all(|w| w[0].difficulty >= w[1].difficulty));
+        assert_eq!(
+            explanation.price,
+            (score.price_multiplier * explanation.base_price as f64) as i64
+        );
+        assert!(explanation.price_multiplier > 1.0);
+    }
+
     #[test]
     fn test_score_fancy() {
         let address = Address::from_str("0x99927777d11dDdFfFfF79b93bB00BBbB5fff5553").unwrap();
@@ -652,4 +1071,180 @@ mod tests {
             assert_eq!(total, total2);
         }
     }
+
+    #[test]
+    fn test_brute_force_zero_bytes() {
+        for num_bytes in 1..4u32 {
+            let mut zero_bytes = vec![0u64; num_bytes as usize + 1];
+            for i in 0..(1u64 << (8 * num_bytes)) {
+                let mut no_zero_bytes = 0;
+                for j in 0..num_bytes {
+                    if (i >> (8 * j)) & 0xff == 0 {
+                        no_zero_bytes += 1;
+                    }
+                }
+                zero_bytes[no_zero_bytes] += 1;
+            }
+            for (i, brute) in zero_bytes.iter().enumerate() {
+                let expected = zero_bytes_combinations(i as u64, num_bytes as u64);
+                println!("zero bytes: {}/{}: {} vs {}", i, num_bytes, brute, expected);
+                assert!((expected - *brute as f64).abs() < 0.0001);
+            }
+        }
+        assert_eq!(zero_bytes_difficulty(0, 20), 1.0);
+        // one zero byte in 20 is roughly 20/256 chance
+        assert!((zero_bytes_difficulty(1, 20) - 13.3).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_brute_force_mixed_snake() {
+        // digits and cased letters, letters are half as likely as digits
+        let symbols = (0..22u8)
+            .map(|s| (s, s >= 10, if s < 10 { 1.0 / 16.0 } else { 1.0 / 32.0 }))
+            .collect::<Vec<_>>();
+        let total = 5;
+        let mut case_tail = vec![0.0f64; total];
+        let mut letters_tail = vec![0.0f64; total];
+        for idx in 0..22usize.pow(total as u32) {
+            let chars = (0..total)
+                .map(|pos| symbols[idx / 22usize.pow(pos as u32) % 22])
+                .collect::<Vec<_>>();
+            let probability = chars.iter().map(|c| c.2).product::<f64>();
+            let pairs = chars.windows(2).filter(|w| w[0].0 == w[1].0);
+            let letter_pairs = pairs.clone().filter(|w| w[0].1).count();
+            for tail in &mut case_tail[..=pairs.count()] {
+                *tail += probability;
+            }
+            for tail in &mut letters_tail[..=letter_pairs] {
+                *tail += probability;
+            }
+        }
+        for n in 0..total {
+            let case_difficulty = snake_need_case_difficulty(n as i64, total as u64);
+            let letters_difficulty = snake_need_letters_difficulty(n as i64, total as u64);
+            assert!((case_difficulty * case_tail[n] - 1.0).abs() < 1e-9, "{n}");
+            assert!(
+                (letters_difficulty * letters_tail[n] - 1.0).abs() < 1e-9,
+                "{n}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_score_zero_bytes() {
+        let address = Address::from_str("0x00120000340000560000780000ab0000cd00ef00").unwrap();
+        let score = score_fancy(address);
+        let entry = score.scores.get("zero_bytes").unwrap();
+        assert_eq!(entry.score, 13.0);
+        assert_eq!(entry.difficulty, zero_bytes_difficulty(13, 20));
+        assert_eq!(score.category, "zero_bytes");
+    }
+
+    #[test]
+    fn test_score_hexspeak() {
+        let address = Address::from_str("0xdeadbeef9912345678901234567890123456789a").unwrap();
+        let score = score_fancy(address);
+        let entry = score.scores.get("hexspeak").unwrap();
+        assert_eq!(entry.score, 8.0);
+        let candidates = get_hexspeak_dictionary().candidates(8) as f64;
+        // case exact matches of long words anywhere are rarer, they add to the tail
+        assert!(entry.difficulty >= 16.0f64.powf(8.0) / candidates / 2.0);
+    }
+
+    #[test]
+    fn test_brute_force_patterns() {
+        let num_ciphers = 5;
+        let total = 16u64.pow(num_ciphers as u32);
+        let mut sequences = vec![0u64; num_ciphers + 1];
+        let mut palindromes = vec![0u64; num_ciphers + 1];
+        for i in 0..total {
+            let nibbles = (0..num_ciphers)
+                .map(|j| ((i >> (4 * j)) & 0xf) as u8)
+                .collect::<Vec<u8>>();
+            sequences[longest_sequence_run(&nibbles) as usize] += 1;
+            palindromes[longest_palindrome(&nibbles) as usize] += 1;
+        }
+        for length in 1..=num_ciphers {
+            let at_least = |counts: &[u64], from: usize| -> f64 {
+                counts[from..].iter().sum::<u64>() as f64 / total as f64
+            };
+
+            let brute = at_least(&sequences, length);
+            let math = if length == 1 {
+                1.0
+            } else {
+                sequence_run_probability(length as u64 - 1, num_ciphers as u64 - 1)
+            };
+            println!("sequence: {}: {} vs {}", length, brute, math);
+            assert!((brute - math).abs() < 1e-12);
+
+            // estimate has to be conservative but close
+            let brute = at_least(&palindromes, length);
+            let math = 1.0 / palindrome_difficulty(length as u64, num_ciphers as u64);
+            println!("palindrome: {}: {} vs {}", length, brute, math);
+            assert!(math >= brute - 1e-12);
+            assert!(math <= brute * 1.2);
+        }
+    }
+
+    /// Periodic segments of every period 2..=8 against periodic_difficulty, all 16^6 strings
+    /// for periods 2 and 3, random full addresses for longer periods
+    #[test]
+    fn test_brute_force_periodic() {
+        let num_ciphers = 6;
+        let total = 16u64.pow(num_ciphers as u32);
+        let mut periodic = vec![0u64; num_ciphers + 1];
+        let mut nibbles = [0u8; 6];
+        for i in 0..total {
+            for (j, nibble) in nibbles.iter_mut().enumerate() {
+                *nibble = ((i >> (4 * j)) & 0xf) as u8;
+            }
+            periodic[longest_periodic_segment(&nibbles) as usize] += 1;
+        }
+        // pure snakes are not periodic
+        assert_eq!(longest_periodic_segment(&[0; 6]), 0);
+        assert_eq!(longest_periodic_segment(&[0, 0, 1, 0, 0, 1]), 6);
+        for length in 4..=num_ciphers {
+            let brute = periodic[length..].iter().sum::<u64>() as f64 / total as f64;
+            let math = 1.0 / periodic_difficulty(length as u64, num_ciphers as u64);
+            println!("periodic: {}: {} vs {}", length, brute, math);
+            assert!(math >= brute - 1e-12);
+            assert!(math <= brute * 1.2);
+        }
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(28);
+        let samples = 400_000;
+        let mut periodic = [0u64; 41];
+        let mut nibbles = [0u8; 40];
+        for _ in 0..samples {
+            for nibble in nibbles.iter_mut() {
+                *nibble = rng.random_range(0..16);
+            }
+            periodic[longest_periodic_segment(&nibbles) as usize] += 1;
+        }
+        for length in 4..=9 {
+            let hits = periodic[length..].iter().sum::<u64>() as f64;
+            let expected = samples as f64 / periodic_difficulty(length as u64, 40);
+            println!("periodic 40: {}: {} vs {}", length, hits, expected);
+            // union bound over periods is conservative, 4 sigma of sampling noise
+            assert!(hits <= expected + 4.0 * expected.sqrt());
+            assert!(hits >= expected * 0.8 - 4.0 * expected.sqrt());
+        }
+    }
+
+    #[test]
+    fn test_score_patterns() {
+        let address = Address::from_str("0xabcabcabcabcabc0123456789a1234554321ffff").unwrap();
+        let score = score_fancy(address);
+        assert_eq!(score.scores.get("periodic_repeat").unwrap().score, 15.0);
+        assert_eq!(score.scores.get("sequence_run").unwrap().score, 11.0);
+        assert_eq!(score.scores.get("palindrome").unwrap().score, 10.0);
+
+        let address = Address::from_str("0x0123456789abcdef01233210fedcba9876543210").unwrap();
+        let score = score_fancy(address);
+        let palindrome = score.scores.get("palindrome").unwrap();
+        assert_eq!(palindrome.score, 40.0);
+        assert_eq!(palindrome.difficulty, 16.0f64.powf(20.0));
+        assert_eq!(score.scores.get("sequence_run").unwrap().score, 20.0);
+    }
 }
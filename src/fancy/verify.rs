@@ -0,0 +1,314 @@
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use crate::fancy::{recompute_fancy, FancyDbObjMin};
+use crate::types::DbAddress;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyMismatch {
+    pub line: usize,
+    pub address: Option<String>,
+    pub computed: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub total: usize,
+    pub verified: usize,
+    pub mismatches: Vec<VerifyMismatch>,
+    pub categories: BTreeMap<String, usize>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Record with line number in input file, or parse error of that line
+pub type ParsedRecord = (usize, Result<FancyDbObjMin, String>);
+
+/// Fields of one CSV record. Fields can be quoted, quote inside is doubled ("")
+fn parse_csv_fields(record: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let mut chars = record.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            ',' => {
+                fields.push(if quoted {
+                    std::mem::take(&mut field)
+                } else {
+                    std::mem::take(&mut field).trim().to_string()
+                });
+                quoted = false;
+            }
+            '"' if !quoted && field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+                quoted = true;
+            }
+            '"' => return Err("Unexpected quote inside field".to_string()),
+            c if quoted && !c.is_whitespace() => {
+                return Err("Unexpected character after quoted field".to_string())
+            }
+            _ if quoted => {}
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("Quoted field is not terminated".to_string());
+    }
+    fields.push(if quoted {
+        field
+    } else {
+        field.trim().to_string()
+    });
+    Ok(fields)
+}
+
+/// Line number where record starts and its fields or parse error
+pub type CsvRecord = (usize, Result<Vec<String>, String>);
+
+/// CSV header and records, empty lines are skipped. Quoted fields may span lines.
+/// Broken header is an error, no record could be read without it
+pub fn read_csv(content: &str) -> Result<(Vec<String>, Vec<CsvRecord>), AddressologyError> {
+    let mut records = Vec::new();
+    let mut lines = content.lines().enumerate();
+    while let Some((idx, line)) = lines.next() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut record = line.to_string();
+        // odd number of quotes means quoted field continues on next line
+        while record.matches('"').count() % 2 == 1 {
+            let Some((_, next)) = lines.next() else {
+                break;
+            };
+            record.push('\n');
+            record.push_str(next);
+        }
+        records.push((idx + 1, parse_csv_fields(&record)));
+    }
+    let mut records = records.into_iter();
+    let header = match records.next() {
+        Some((line_no, header)) => {
+            header.map_err(|e| err_custom_create!("Invalid CSV header on line {line_no}: {e}"))?
+        }
+        None => Vec::new(),
+    };
+    Ok((header, records.collect()))
+}
+
+/// Value of the first given column that is present and not empty
+pub fn csv_field<'a>(header: &[String], values: &'a [String], names: &[&str]) -> Option<&'a str> {
+    header
+        .iter()
+        .position(|column| names.contains(&column.as_str()))
+        .map(|idx| values[idx].as_str())
+        .filter(|value| !value.is_empty())
+}
+
+pub fn parse_csv_record(
+    header: &[String],
+    values: &[String],
+) -> Result<FancyDbObjMin, AddressologyError> {
+    if values.len() != header.len() {
+        return Err(err_custom_create!(
+            "Expected {} columns, got {}",
+            header.len(),
+            values.len()
+        ));
+    }
+    let field = |names: &[&str]| csv_field(header, values, names);
+    let parse_address = |value: &str| {
+        DbAddress::from_str(value).map_err(|e| err_custom_create!("Invalid address {value}: {e}"))
+    };
+    Ok(FancyDbObjMin {
+        address: parse_address(field(&["address"]).ok_or(err_custom_create!("Missing address"))?)?,
+        salt: field(&["salt"])
+            .ok_or(err_custom_create!("Missing salt"))?
+            .to_string(),
+        factory: field(&["factory"]).map(parse_address).transpose()?,
+        init_code_hash: field(&["init_code_hash", "initCodeHash"]).map(|v| v.to_string()),
+        public_key_base: field(&["public_key_base", "publicKeyBase"]).map(|v| v.to_string()),
+        create3_scheme: field(&["create3_scheme", "create3Scheme"]).map(|v| v.to_string()),
+        caller: field(&["caller"]).map(parse_address).transpose()?,
+        chain_id: field(&["chain_id", "chainId"])
+            .map(|v| {
+                v.parse::<i64>()
+                    .map_err(|e| err_custom_create!("Invalid chain id {v}: {e}"))
+            })
+            .transpose()?,
+    })
+}
+
+/// Non-empty lines of JSONL input with line numbers
+pub fn read_jsonl(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+pub fn parse_csv_input(content: &str) -> Result<Vec<ParsedRecord>, AddressologyError> {
+    let (header, records) = read_csv(content)?;
+    Ok(records
+        .into_iter()
+        .map(|(line_no, values)| {
+            (
+                line_no,
+                values.and_then(|values| {
+                    parse_csv_record(&header, &values).map_err(|e| e.to_string())
+                }),
+            )
+        })
+        .collect())
+}
+
+pub fn parse_jsonl_input(content: &str) -> Vec<ParsedRecord> {
+    read_jsonl(content)
+        .map(|(line_no, line)| {
+            (
+                line_no,
+                serde_json::from_str::<FancyDbObjMin>(line).map_err(|e| e.to_string()),
+            )
+        })
+        .collect()
+}
+
+/// Read records from JSONL (camelCase FancyDbObjMin) or CSV with header line
+pub fn parse_verify_input(content: &str) -> Result<Vec<ParsedRecord>, AddressologyError> {
+    if content.trim_start().starts_with('{') {
+        Ok(parse_jsonl_input(content))
+    } else {
+        parse_csv_input(content)
+    }
+}
+
+/// Map items in scoped threads, work is split evenly between threads and order is kept
+pub fn map_in_threads<T: Sync, R: Send>(
+    items: &[T],
+    threads: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let chunk_size = items.len().div_ceil(threads.max(1)).max(1);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("worker thread panicked"))
+            .collect()
+    })
+}
+
+fn verify_record(
+    line: usize,
+    record: &Result<FancyDbObjMin, String>,
+) -> Result<String, VerifyMismatch> {
+    let record = record.as_ref().map_err(|err| VerifyMismatch {
+        line,
+        address: None,
+        computed: None,
+        error: Some(err.clone()),
+    })?;
+    let recomputed = recompute_fancy(record).map_err(|err| VerifyMismatch {
+        line,
+        address: Some(record.address.to_string()),
+        computed: None,
+        error: Some(err.to_string()),
+    })?;
+    if recomputed.address != record.address {
+        return Err(VerifyMismatch {
+            line,
+            address: Some(record.address.to_string()),
+            computed: Some(recomputed.address.to_string()),
+            error: None,
+        });
+    }
+    Ok(recomputed.category)
+}
+
+/// Recompute and re-score all records, work is split evenly between threads
+pub fn verify_records(records: &[ParsedRecord], threads: usize) -> VerifyReport {
+    let results = map_in_threads(records, threads, |(line, record)| {
+        verify_record(*line, record)
+    });
+
+    let mut report = VerifyReport {
+        total: records.len(),
+        ..Default::default()
+    };
+    for result in results {
+        match result {
+            Ok(category) => {
+                report.verified += 1;
+                *report.categories.entry(category).or_default() += 1;
+            }
+            Err(mismatch) => report.mismatches.push(mismatch),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_records() {
+        let csv = "address,salt,factory,init_code_hash,public_key_base\n\
+            0x31585b5cd5557777376822555552bb555ee18882,0x9a07547b2ac4220006e585000000000000000000000000000000000000000000,0x9E3F8eaE49E442A323EF2094f277Bf62752E6995,,\n\
+            \n\
+            0x7C92B1e0ea075Aa010A0b464764d25665221f666,0x04b7ae1bbb6c98775b62d9fb8e68ff05630f3b6fd7068dccce504dac9cb64f47,,,a71f7ec030f9ad20f8cc67fd116eb75c2117e90e649cdf293d655dc34d4b15e9fe66dfd3b79a74bf2ee878148922a34a5db044dd091731aba2404a207e2b5a05\n\
+            0x0000000000000000000000000000000000000001,0x9a07547b2ac4220006e585000000000000000000000000000000000000000000,0x9E3F8eaE49E442A323EF2094f277Bf62752E6995,,\n\
+            0x31585b5cd5557777376822555552bb555ee18882,0x01\n";
+        let records = parse_verify_input(csv).unwrap();
+        assert_eq!(records.len(), 4);
+        assert!(records[3].1.is_err());
+
+        let report = verify_records(&records, 3);
+        assert_eq!(report.total, 4);
+        assert_eq!(report.verified, 2);
+        assert_eq!(report.categories.values().sum::<usize>(), 2);
+        assert!(!report.is_ok());
+        assert_eq!(report.mismatches.len(), 2);
+        assert_eq!(report.mismatches[0].line, 5);
+        assert_eq!(
+            report.mismatches[0].computed.as_deref(),
+            Some("0x31585b5cd5557777376822555552bb555ee18882")
+        );
+        assert_eq!(report.mismatches[1].line, 6);
+
+        let jsonl = r#"{"address":"0x31585b5cd5557777376822555552bb555ee18882","salt":"0x9a07547b2ac4220006e585000000000000000000000000000000000000000000","factory":"0x9e3f8eae49e442a323ef2094f277bf62752e6995","publicKeyBase":null}"#;
+        let report = verify_records(&parse_verify_input(jsonl).unwrap(), 8);
+        assert!(report.is_ok());
+        assert_eq!(report.verified, 1);
+
+        assert!(parse_verify_input("").unwrap().is_empty());
+        let err = parse_verify_input("address,\"salt\nfoo\n").unwrap_err();
+        assert!(err.to_string().contains("Invalid CSV header on line 1"));
+    }
+}
@@ -1,32 +1,220 @@
 use crate::err_custom_create;
 use crate::error::AddressologyError;
 use secp256k1::{PublicKey, Secp256k1};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 use tiny_keccak::{Hasher, Keccak};
-pub fn salt_to_guarded_salt(salt: &[u8]) -> [u8; 32] {
-    //take last 32 bytes
+
+fn keccak_concat(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    for part in parts {
+        hasher.update(part);
+    }
     let mut result = [0; 32];
-    result.copy_from_slice(&salt[0..32]);
+    hasher.finalize(&mut result);
     result
 }
 
-pub fn compute_create3_command(factory: &str, salt: &str) -> Result<String, AddressologyError> {
-    log::debug!("Computing create3 for factory: {}, salt: {}", factory, salt);
+/// Salt guard of CreateX (_guard in CreateX.sol).
+/// First 20 bytes of salt can be the caller (permissioned deploy) or zero address,
+/// byte 21 is the cross-chain redeploy protection flag (0x01 - on, 0x00 - off).
+/// Caller is required for salts with non-zero prefix, only then it is known whether
+/// the deploy is permissioned or the prefix is random.
+pub fn salt_to_guarded_salt(
+    salt: &[u8],
+    caller: Option<&[u8]>,
+    chain_id: Option<u64>,
+) -> Result<[u8; 32], AddressologyError> {
+    if salt.len() != 32 {
+        return Err(err_custom_create!("Salt len has to be 32 bytes"));
+    }
+    let sender_bytes = &salt[0..20];
+    let redeploy_protection = salt[20];
+    let is_zero_address = sender_bytes.iter().all(|b| *b == 0);
+    if !is_zero_address && caller.is_none() {
+        return Err(err_custom_create!(
+            "Salt starts with address 0x{}, caller is required to compute CreateX guarded salt",
+            hex::encode(sender_bytes)
+        ));
+    }
+    let is_caller = caller.is_some_and(|caller| caller == sender_bytes);
+    let chain_id_word = || {
+        chain_id
+            .map(|chain_id| {
+                let mut word = [0u8; 32];
+                word[24..].copy_from_slice(&chain_id.to_be_bytes());
+                word
+            })
+            .ok_or(err_custom_create!(
+                "Salt has cross-chain redeploy protection, chain id is required"
+            ))
+    };
 
-    /*if let Some(caller) = &caller {
-        log::info!("Also checking for caller: {}", caller);
-    }*/
+    if is_caller {
+        let mut caller_word = [0u8; 32];
+        caller_word[12..].copy_from_slice(sender_bytes);
+        match redeploy_protection {
+            0x01 => Ok(keccak_concat(&[&caller_word, &chain_id_word()?, salt])),
+            0x00 => Ok(keccak_concat(&[&caller_word, salt])),
+            flag => Err(err_custom_create!(
+                "Invalid salt, CreateX reverts for caller salt with protection flag {:#04x}",
+                flag
+            )),
+        }
+    } else if is_zero_address {
+        match redeploy_protection {
+            0x01 => Ok(keccak_concat(&[&chain_id_word()?, salt])),
+            0x00 => Ok(keccak_concat(&[salt])),
+            flag => Err(err_custom_create!(
+                "Invalid salt, CreateX reverts for zero address salt with protection flag {:#04x}",
+                flag
+            )),
+        }
+    } else {
+        Ok(keccak_concat(&[salt]))
+    }
+}
 
-    /*
-    let caller_bytes = if let Some(caller) = &caller {
-        match hex::decode(caller.replace("0x", "")) {
-            Ok(bytes) => Some(bytes),
-            Err(e) => {
-                return Err(err_custom_create!("Failed to decode caller: {}", e));
+/// Init code of the minimal proxy deployed with CREATE2, which then deploys the contract with CREATE
+#[allow(unused)]
+pub const CREATE3_PROXY_BYTECODE: &str = "0x67363d3d37363d34f03d5260086018f3";
+pub const CREATE3_PROXY_INIT_CODE_HASH: &str =
+    "0x21c35dbe1b344a2488cf3321d6ce542f8e9f305544ff09e4993a62319a497c1f";
+
+/// How CREATE3 factory turns user salt into CREATE2 salt of the proxy
+#[derive(Serialize, Deserialize, EnumIter, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Create3Scheme {
+    /// Salt is used as is (Solady CREATE3, our own factory)
+    #[default]
+    Solady,
+    /// 0xSequence create3 library, salt is used as is
+    Sequence,
+    /// ZeframLou CREATE3Factory, salt = keccak256(deployer ++ salt)
+    ZeframLou,
+    /// CreateX deployCreate3, salt is guarded by salt_to_guarded_salt
+    CreateX,
+}
+
+impl Display for Create3Scheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Create3Scheme::Solady => write!(f, "solady"),
+            Create3Scheme::Sequence => write!(f, "sequence"),
+            Create3Scheme::ZeframLou => write!(f, "zeframlou"),
+            Create3Scheme::CreateX => write!(f, "createx"),
+        }
+    }
+}
+
+impl FromStr for Create3Scheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Create3Scheme::iter()
+            .find(|scheme| scheme.to_string() == s.to_lowercase())
+            .ok_or(format!(
+                "Unknown create3 scheme {s}, possible values: {}",
+                Create3Scheme::iter()
+                    .map(|scheme| scheme.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ))
+    }
+}
+
+/// Canonical deployments of CREATE3 factories (same address on all chains)
+const KNOWN_CREATE3_FACTORIES: [(&str, Create3Scheme); 2] = [
+    (
+        "ba5ed099633d3b313e4d5f7bdc1305d3c28ba5ed",
+        Create3Scheme::CreateX,
+    ),
+    (
+        "9fbb3df7c40da2e5a0de984ffe2ccb7c47cd0abf",
+        Create3Scheme::ZeframLou,
+    ),
+];
+
+impl Create3Scheme {
+    /// Scheme of known factory deployment, it is never applied implicitly
+    pub fn of_known_factory(factory: &str) -> Option<Self> {
+        let factory = factory.trim_start_matches("0x").to_lowercase();
+        KNOWN_CREATE3_FACTORIES
+            .iter()
+            .find(|(known, _)| *known == factory)
+            .map(|(_, scheme)| *scheme)
+    }
+
+    /// Cruncher derives proxy address from plain salt only
+    pub fn is_plain_salt(&self) -> bool {
+        matches!(self, Create3Scheme::Solady | Create3Scheme::Sequence)
+    }
+
+    pub fn proxy_init_code_hash(&self) -> &'static str {
+        match self {
+            Create3Scheme::Solady
+            | Create3Scheme::Sequence
+            | Create3Scheme::ZeframLou
+            | Create3Scheme::CreateX => CREATE3_PROXY_INIT_CODE_HASH,
+        }
+    }
+
+    pub fn derive_salt(
+        &self,
+        salt: &[u8],
+        caller: Option<&[u8]>,
+        chain_id: Option<u64>,
+    ) -> Result<[u8; 32], AddressologyError> {
+        match self {
+            Create3Scheme::Solady | Create3Scheme::Sequence => {
+                let mut result = [0; 32];
+                result.copy_from_slice(&salt[0..32]);
+                Ok(result)
             }
+            Create3Scheme::ZeframLou => {
+                let Some(caller) = caller else {
+                    return Err(err_custom_create!(
+                        "ZeframLou factory salt depends on the deployer, caller is required"
+                    ));
+                };
+                Ok(keccak_concat(&[caller, &salt[0..32]]))
+            }
+            Create3Scheme::CreateX => salt_to_guarded_salt(salt, caller, chain_id),
         }
+    }
+}
+
+/// Plain salt CREATE3 as computed by cruncher, other schemes need compute_create3
+pub fn compute_create3_command(factory: &str, salt: &str) -> Result<String, AddressologyError> {
+    compute_create3(factory, salt, Create3Scheme::Solady, None, None)
+}
+
+pub fn compute_create3(
+    factory: &str,
+    salt: &str,
+    scheme: Create3Scheme,
+    caller: Option<&str>,
+    chain_id: Option<u64>,
+) -> Result<String, AddressologyError> {
+    log::debug!(
+        "Computing create3 for factory: {}, salt: {}, scheme: {}",
+        factory,
+        salt,
+        scheme
+    );
+
+    if let Some(caller) = &caller {
+        log::debug!("Also checking for caller: {}", caller);
+    }
+
+    let caller_bytes = if let Some(caller) = &caller {
+        Some(decode_hex_bytes("Caller", caller, 20)?)
     } else {
         None
-    };*/
+    };
 
     let factory_bytes = match hex::decode(factory.replace("0x", "")) {
         Ok(bytes) => bytes,
@@ -52,7 +240,7 @@ pub fn compute_create3_command(factory: &str, salt: &str) -> Result<String, Addr
             "Salt len has to be 32 bytes (64 characters)"
         ));
     }
-    let guarded_hash_bytes = salt_to_guarded_salt(&salt_bytes);
+    let guarded_hash_bytes = scheme.derive_salt(&salt_bytes, caller_bytes.as_deref(), chain_id)?;
 
     log::trace!("Guarded hash: 0x{}", hex::encode(guarded_hash_bytes));
 
@@ -67,7 +255,7 @@ pub fn compute_create3_command(factory: &str, salt: &str) -> Result<String, Addr
 
     mem.extend_from_slice(&guarded_hash_bytes[0..0x20]);
 
-    let const_hex = "0x21c35dbe1b344a2488cf3321d6ce542f8e9f305544ff09e4993a62319a497c1f";
+    let const_hex = scheme.proxy_init_code_hash();
     let const_bytes = match hex::decode(const_hex.replace("0x", "")) {
         Ok(bytes) => bytes,
         Err(e) => {
@@ -105,6 +293,91 @@ pub fn compute_create3_command(factory: &str, salt: &str) -> Result<String, Addr
     Ok(format!("0x{}", hex::encode(&result.as_slice()[12..])))
 }
 
+fn decode_hex_bytes(name: &str, value: &str, len: usize) -> Result<Vec<u8>, AddressologyError> {
+    let bytes = match hex::decode(value.trim_start_matches("0x")) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return Err(err_custom_create!("Failed to decode {}: {}", name, e));
+        }
+    };
+    if bytes.len() != len {
+        return Err(err_custom_create!(
+            "{} len has to be {} bytes ({} characters)",
+            name,
+            len,
+            len * 2
+        ));
+    }
+    Ok(bytes)
+}
+
+pub fn compute_init_code_hash(init_code: &str) -> Result<String, AddressologyError> {
+    let init_code_bytes = match hex::decode(init_code.trim_start_matches("0x")) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return Err(err_custom_create!("Failed to decode init code: {}", e));
+        }
+    };
+    let mut hasher = Keccak::v256();
+    hasher.update(&init_code_bytes);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    Ok(format!("0x{}", hex::encode(hash)))
+}
+
+/// Standard CREATE2 address: keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))[12..]
+pub fn compute_create2(
+    deployer: &str,
+    salt: &str,
+    init_code_hash: &str,
+) -> Result<String, AddressologyError> {
+    log::debug!(
+        "Computing create2 for deployer: {}, salt: {}, init code hash: {}",
+        deployer,
+        salt,
+        init_code_hash
+    );
+    let deployer_bytes = decode_hex_bytes("Deployer", deployer, 20)?;
+    let salt_bytes = decode_hex_bytes("Salt", salt, 32)?;
+    let init_code_hash_bytes = decode_hex_bytes("Init code hash", init_code_hash, 32)?;
+
+    let mut mem = Vec::with_capacity(0x55);
+    mem.push(0xff);
+    mem.extend_from_slice(&deployer_bytes);
+    mem.extend_from_slice(&salt_bytes);
+    mem.extend_from_slice(&init_code_hash_bytes);
+
+    let mut hasher = Keccak::v256();
+    hasher.update(&mem);
+    let mut result = [0; 32];
+    hasher.finalize(&mut result);
+
+    Ok(format!("0x{}", hex::encode(&result[12..])))
+}
+
+/// Address of contract deployed with CREATE: keccak256(rlp([deployer, nonce]))[12..]
+#[allow(unused)]
+pub fn compute_create_address(deployer: &str, nonce: u8) -> Result<String, AddressologyError> {
+    let deployer_bytes = decode_hex_bytes("Deployer", deployer, 20)?;
+    let mut rlp = vec![0xd6, 0x94];
+    rlp.extend_from_slice(&deployer_bytes);
+    // rlp of single byte below 0x80 is the byte itself, zero is encoded as empty string
+    match nonce {
+        0 => rlp.push(0x80),
+        1..=0x7f => rlp.push(nonce),
+        _ => {
+            rlp[0] = 0xd7;
+            rlp.push(0x81);
+            rlp.push(nonce);
+        }
+    }
+    let mut hasher = Keccak::v256();
+    hasher.update(&rlp);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    Ok(format!("0x{}", hex::encode(&hash[12..])))
+}
+
 pub fn compute_address_command(
     public_key_base: &str,
     private_key_add: &str,
@@ -157,6 +430,56 @@ pub fn compute_address_command(
     Ok(format!("0x{}", hex::encode(&hash[12..])))
 }
 
+/// Final private key of split-key vanity address: private_key_base + private_key_add (mod n)
+pub fn combine_private_key(
+    private_key_base: &str,
+    private_key_add: &str,
+) -> Result<String, AddressologyError> {
+    let private_key_base = decode_hex_bytes("Private key base", private_key_base, 32)?;
+    let private_key_add = decode_hex_bytes("Private key add", private_key_add, 32)?;
+    let private_key = match secp256k1::SecretKey::from_slice(&private_key_base) {
+        Ok(private_key) => private_key,
+        Err(e) => {
+            return Err(err_custom_create!(
+                "Failed to decode private key base: {}",
+                e
+            ));
+        }
+    };
+    let mut tweak = [0u8; 32];
+    tweak.copy_from_slice(&private_key_add);
+    let tweak = match secp256k1::Scalar::from_be_bytes(tweak) {
+        Ok(tweak) => tweak,
+        Err(e) => {
+            return Err(err_custom_create!(
+                "Failed to decode private key add: {}",
+                e
+            ));
+        }
+    };
+    let combined = match private_key.add_tweak(&tweak) {
+        Ok(combined) => combined,
+        Err(e) => {
+            return Err(err_custom_create!("Failed to combine private keys: {}", e));
+        }
+    };
+    Ok(format!("0x{}", hex::encode(combined.secret_bytes())))
+}
+
+/// Public key base in format expected by cruncher -z and compute_address_command
+/// (uncompressed key without 04 prefix, 128 hex characters)
+pub fn compute_public_key_base(private_key: &str) -> Result<String, AddressologyError> {
+    let private_key = decode_hex_bytes("Private key", private_key, 32)?;
+    let private_key = match secp256k1::SecretKey::from_slice(&private_key) {
+        Ok(private_key) => private_key,
+        Err(e) => {
+            return Err(err_custom_create!("Failed to decode private key: {}", e));
+        }
+    };
+    let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &private_key);
+    Ok(hex::encode(&public_key.serialize_uncompressed()[1..]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -178,6 +501,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_combine_private_key() {
+        let private_key_base = "0x7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+        let private_key_add = "0x04b7ae1bbb6c98775b62d9fb8e68ff05630f3b6fd7068dccce504dac9cb64f47";
+        let public_key_base = compute_public_key_base(private_key_base).unwrap();
+        assert_eq!(public_key_base.len(), 128);
+
+        let combined = combine_private_key(private_key_base, private_key_add).unwrap();
+        assert_eq!(
+            crate::keystore::private_key_to_address(&hex::decode(&combined[2..]).unwrap()).unwrap(),
+            compute_address_command(&public_key_base, private_key_add).unwrap()
+        );
+
+        // addition wraps around curve order
+        let order_minus_one = "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140";
+        let two = "0x0000000000000000000000000000000000000000000000000000000000000002";
+        assert_eq!(
+            combine_private_key(order_minus_one, two).unwrap(),
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert!(combine_private_key(private_key_base, "0x01").is_err());
+    }
+
     #[test]
     fn test_compute_create3_command() {
         let factory = "0x9E3F8eaE49E442A323EF2094f277Bf62752E6995".to_string();
@@ -190,4 +536,237 @@ mod tests {
             "0x31585b5cd5557777376822555552bb555ee18882".to_string()
         );
     }
+
+    #[test]
+    fn test_compute_create2() {
+        // examples from EIP-1014
+        let zero_address = "0x0000000000000000000000000000000000000000";
+        let zero_salt = "0x0000000000000000000000000000000000000000000000000000000000000000";
+        let vectors = [
+            (
+                zero_address,
+                zero_salt,
+                "0x00",
+                "0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38",
+            ),
+            (
+                "0xdeadbeef00000000000000000000000000000000",
+                zero_salt,
+                "0x00",
+                "0xB928f69Bb1D91Cd65274e3c79d8986362984fDA3",
+            ),
+            (
+                "0xdeadbeef00000000000000000000000000000000",
+                "0x000000000000000000000000feed000000000000000000000000000000000000",
+                "0x00",
+                "0xD04116cDd17beBE565EB2422F2497E06cC1C9833",
+            ),
+            (
+                zero_address,
+                zero_salt,
+                "0xdeadbeef",
+                "0x70f2b2914A2a4b783FaEFb75f459A580616Fcb5e",
+            ),
+            (
+                "0x00000000000000000000000000000000deadbeef",
+                "0x00000000000000000000000000000000000000000000000000000000cafebabe",
+                "0xdeadbeef",
+                "0x60f3f640a8508fC6a86d45DF051962668E1e8AC7",
+            ),
+            (
+                zero_address,
+                zero_salt,
+                "0x",
+                "0xE33C0C7F7df4809055C3ebA6c09CFe4BaF1BD9e0",
+            ),
+        ];
+        for (deployer, salt, init_code, expected) in vectors {
+            let init_code_hash = compute_init_code_hash(init_code).unwrap();
+            let result = compute_create2(deployer, salt, &init_code_hash).unwrap();
+            assert_eq!(result, expected.to_lowercase());
+        }
+    }
+
+    #[test]
+    fn test_compute_create2_uniswap_v2_pair() {
+        // USDC/WETH pair deployed by Uniswap V2 factory, salt = keccak256(token0 ++ token1)
+        let factory = "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f";
+        let init_code_hash = "0x96e8ac4277198ff8b6f785478aa9a39f403cb768dd02cbee326c3e7da348845f";
+        let tokens = hex::decode(
+            "a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+        )
+        .unwrap();
+        let mut salt = [0u8; 32];
+        let mut hasher = Keccak::v256();
+        hasher.update(&tokens);
+        hasher.finalize(&mut salt);
+
+        let result = compute_create2(factory, &hex::encode(salt), init_code_hash).unwrap();
+        assert_eq!(
+            result,
+            "0xB4e16d0168e52d35CaCD2c6185b44281Ec28C9Dc".to_lowercase()
+        );
+
+        assert!(compute_create2(factory, "0x00", init_code_hash).is_err());
+    }
+
+    #[test]
+    fn test_compute_create_address() {
+        let deployer = "0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0";
+        assert_eq!(
+            compute_create_address(deployer, 0).unwrap(),
+            "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d"
+        );
+        assert_eq!(
+            compute_create_address(deployer, 1).unwrap(),
+            "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8"
+        );
+        assert_eq!(
+            compute_create_address(deployer, 2).unwrap(),
+            "0xf778b86fa74e846c4f0a1fbd1335fe81c00a0c91"
+        );
+    }
+
+    #[test]
+    fn test_create3_schemes() {
+        // all known schemes deploy the same 16 byte proxy
+        assert_eq!(
+            compute_init_code_hash(CREATE3_PROXY_BYTECODE).unwrap(),
+            CREATE3_PROXY_INIT_CODE_HASH
+        );
+
+        let factory = "0x9E3F8eaE49E442A323EF2094f277Bf62752E6995";
+        let caller = "0x00000000000000000000000000000000deadbeef";
+        let salt = "0x9a07547b2ac4220006e585000000000000000000000000000000000000000000";
+
+        // deployed by our factory, which uses the salt as is
+        for scheme in [Create3Scheme::Solady, Create3Scheme::Sequence] {
+            let result = compute_create3(factory, salt, scheme, None, None).unwrap();
+            assert_eq!(result, "0x31585b5cd5557777376822555552bb555ee18882");
+        }
+
+        // legacy path is plain salt also for known factories
+        let createx = "0xba5Ed099633D3B313e4D5F7bdc1305d3c28ba5Ed";
+        assert_eq!(
+            compute_create3_command(createx, salt).unwrap(),
+            compute_create3(createx, salt, Create3Scheme::Solady, None, None).unwrap()
+        );
+
+        // expected addresses are computed outside of this crate from getDeployed of
+        // ZeframLou CREATE3Factory and computeCreate3Address(_guard(salt)) of CreateX
+        let zeframlou = "0x9fBB3DF7C40Da2e5A0dE984fFE2CCB7C47cd0ABf";
+        let result = compute_create3(
+            zeframlou,
+            salt,
+            Create3Scheme::ZeframLou,
+            Some(caller),
+            None,
+        );
+        assert_eq!(
+            result.unwrap(),
+            "0x4ff5ce1cfc2191b895b33b8589f9efde7c3b06e1"
+        );
+        assert!(compute_create3(zeframlou, salt, Create3Scheme::ZeframLou, None, None).is_err());
+        for (salt, expected) in [
+            // permissioned, cross-chain redeploy protection on chain 8453
+            (
+                "0x00000000000000000000000000000000deadbeef014242424242424242424242",
+                "0x6899af391f9555ae48725627adae7b738f935296",
+            ),
+            // permissioned only
+            (
+                "0x00000000000000000000000000000000deadbeef004242424242424242424242",
+                "0x7d3a297765872f268a97039998471f72e16de138",
+            ),
+            // pseudo-random, prefix is not the caller
+            (
+                "0x0000000000000000000000000000000000c0ffee014242424242424242424242",
+                "0x44dd2c9d2364edd3270e973427198937824ed113",
+            ),
+        ] {
+            let result = compute_create3(
+                createx,
+                salt,
+                Create3Scheme::CreateX,
+                Some(caller),
+                Some(8453),
+            );
+            assert_eq!(result.unwrap(), expected);
+        }
+
+        assert_eq!(
+            Create3Scheme::of_known_factory(createx),
+            Some(Create3Scheme::CreateX)
+        );
+        assert_eq!(Create3Scheme::of_known_factory(factory), None);
+        assert!(Create3Scheme::Sequence.is_plain_salt());
+        assert!(!Create3Scheme::ZeframLou.is_plain_salt());
+        assert_eq!(
+            Create3Scheme::from_str("ZeframLou").unwrap(),
+            Create3Scheme::ZeframLou
+        );
+        assert!(Create3Scheme::from_str("unknown").is_err());
+    }
+
+    #[test]
+    fn test_createx_guarded_salt() {
+        // expected guarded salts are computed outside of this crate from _guard in CreateX.sol,
+        // msg.sender 0x..deadbeef and block.chainid 8453
+        let caller = hex::decode("00000000000000000000000000000000deadbeef").unwrap();
+        let guard = |salt: &str| {
+            let salt = hex::decode(salt).unwrap();
+            salt_to_guarded_salt(&salt, Some(&caller), Some(8453)).map(hex::encode)
+        };
+        for (salt, expected) in [
+            // permissioned with cross-chain redeploy protection
+            (
+                "00000000000000000000000000000000deadbeef014242424242424242424242",
+                "5a0e7de0fabe2aa229d83a1ce5774c1ca2723524fb21af10c56b6c0e6203e4c8",
+            ),
+            // permissioned only
+            (
+                "00000000000000000000000000000000deadbeef004242424242424242424242",
+                "890b56989af2fc0a3b7fcdce4d70e40025825e011e33f9c43759674429502444",
+            ),
+            // zero address prefix with cross-chain redeploy protection
+            (
+                "0000000000000000000000000000000000000000014242424242424242424242",
+                "909c6adb59d1ff5b3af3a2f650483c9968d586f5568d4d0abf53c24e33ef5799",
+            ),
+            // zero address prefix without protection
+            (
+                "0000000000000000000000000000000000000000004242424242424242424242",
+                "f28978ae2474cf553a0136f459710361308f3c5e96c17031a65e61b68f8122b6",
+            ),
+            // pseudo-random, prefix is not the caller so protection flag is ignored
+            (
+                "0000000000000000000000000000000000c0ffee004242424242424242424242",
+                "f809649b8c882097831f83c22bcd463b0491fff55733eb743d1f02b6f1791714",
+            ),
+            (
+                "0000000000000000000000000000000000c0ffee014242424242424242424242",
+                "e216631ef9f3893ff8fa7dc6dce69935ffddbdf8f084adffd65045fe02095eee",
+            ),
+            (
+                "0000000000000000000000000000000000c0ffeeff4242424242424242424242",
+                "f5d4a68b452e269ce1156fa5b8bb5ec9373913dc1caddb2f96c699281e397052",
+            ),
+        ] {
+            assert_eq!(guard(salt).unwrap(), expected, "salt {salt}");
+        }
+
+        // CreateX reverts for other protection flags of permissioned and zero address salts
+        assert!(guard("00000000000000000000000000000000deadbeef024242424242424242424242").is_err());
+        assert!(guard("00000000000000000000000000000000000000007f4242424242424242424242").is_err());
+
+        let salt = hex::decode("00000000000000000000000000000000deadbeef014242424242424242424242")
+            .unwrap();
+        // protection without known chain id cannot be computed
+        assert!(salt_to_guarded_salt(&salt, Some(&caller), None).is_err());
+        // without caller, permissioned salt cannot be told from random one
+        assert!(salt_to_guarded_salt(&salt, None, Some(8453)).is_err());
+        let salt = hex::decode("0000000000000000000000000000000000000000004242424242424242424242")
+            .unwrap();
+        assert!(salt_to_guarded_salt(&salt, None, None).is_ok());
+    }
 }
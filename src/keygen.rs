@@ -0,0 +1,174 @@
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+use sha2::Sha512;
+
+/// First account of standard Ethereum wallets (BIP-44)
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+const HARDENED_OFFSET: u32 = 1 << 31;
+
+pub fn generate_private_key() -> [u8; 32] {
+    let mut rng = rand::rng();
+    loop {
+        let candidate: [u8; 32] = rng.random();
+        // chance of hitting invalid key (zero or above curve order) is negligible, but possible
+        if SecretKey::from_slice(&candidate).is_ok() {
+            return candidate;
+        }
+    }
+}
+
+pub fn generate_mnemonic(word_count: usize) -> Result<String, AddressologyError> {
+    if ![12, 15, 18, 21, 24].contains(&word_count) {
+        return Err(err_custom_create!(
+            "Mnemonic has to have 12, 15, 18, 21 or 24 words, got {word_count}"
+        ));
+    }
+    let mut entropy = vec![0u8; word_count * 4 / 3];
+    rand::rng().fill(entropy.as_mut_slice());
+    let mnemonic = bip39::Mnemonic::from_entropy(&entropy)
+        .map_err(|e| err_custom_create!("Failed to create mnemonic: {e}"))?;
+    Ok(mnemonic.to_string())
+}
+
+fn parse_derivation_path(path: &str) -> Result<Vec<u32>, AddressologyError> {
+    let mut parts = path.trim().split('/');
+    if parts.next() != Some("m") {
+        return Err(err_custom_create!(
+            "Derivation path has to start with m/, got {path}"
+        ));
+    }
+    parts
+        .map(|part| {
+            let (index, hardened) = match part.strip_suffix('\'') {
+                Some(index) => (index, true),
+                None => (part, false),
+            };
+            let index = index
+                .parse::<u32>()
+                .ok()
+                .filter(|index| *index < HARDENED_OFFSET)
+                .ok_or(err_custom_create!("Invalid derivation path element {part}"))?;
+            Ok(if hardened {
+                index + HARDENED_OFFSET
+            } else {
+                index
+            })
+        })
+        .collect()
+}
+
+/// BIP-32 private key derivation from seed
+pub fn derive_private_key(seed: &[u8], path: &str) -> Result<[u8; 32], AddressologyError> {
+    let hmac_sha512 = |key: &[u8], data: &[&[u8]]| {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("hmac accepts any key length");
+        for part in data {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes()
+    };
+    let split = |output: &[u8]| {
+        let mut key = [0u8; 32];
+        let mut chain_code = [0u8; 32];
+        key.copy_from_slice(&output[0..32]);
+        chain_code.copy_from_slice(&output[32..64]);
+        (key, chain_code)
+    };
+
+    let secp = Secp256k1::new();
+    let (key, mut chain_code) = split(&hmac_sha512(b"Bitcoin seed", &[seed]));
+    let mut secret_key = SecretKey::from_slice(&key)
+        .map_err(|e| err_custom_create!("Invalid master key, use different seed: {e}"))?;
+
+    for index in parse_derivation_path(path)? {
+        let output = if index >= HARDENED_OFFSET {
+            hmac_sha512(
+                &chain_code,
+                &[&[0u8], &secret_key.secret_bytes(), &index.to_be_bytes()],
+            )
+        } else {
+            let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+            hmac_sha512(
+                &chain_code,
+                &[&public_key.serialize(), &index.to_be_bytes()],
+            )
+        };
+        let (tweak, next_chain_code) = split(&output);
+        let tweak = Scalar::from_be_bytes(tweak)
+            .map_err(|e| err_custom_create!("Invalid child key at index {index}: {e}"))?;
+        secret_key = secret_key
+            .add_tweak(&tweak)
+            .map_err(|e| err_custom_create!("Invalid child key at index {index}: {e}"))?;
+        chain_code = next_chain_code;
+    }
+    Ok(secret_key.secret_bytes())
+}
+
+pub fn private_key_from_mnemonic(
+    phrase: &str,
+    passphrase: &str,
+    path: &str,
+) -> Result<[u8; 32], AddressologyError> {
+    let mnemonic =
+        bip39::Mnemonic::parse(phrase).map_err(|e| err_custom_create!("Invalid mnemonic: {e}"))?;
+    derive_private_key(&mnemonic.to_seed(passphrase), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::compute_public_key_base;
+    use crate::keystore::private_key_to_address;
+
+    #[test]
+    fn test_derive_from_mnemonic() {
+        // default development mnemonic of hardhat and anvil
+        let private_key = private_key_from_mnemonic(
+            "test test test test test test test test test test test junk",
+            "",
+            DEFAULT_DERIVATION_PATH,
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(private_key),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+        assert_eq!(
+            private_key_to_address(&private_key).unwrap(),
+            "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+
+        let second = private_key_from_mnemonic(
+            "test test test test test test test test test test test junk",
+            "",
+            "m/44'/60'/0'/0/1",
+        )
+        .unwrap();
+        assert_eq!(
+            private_key_to_address(&second).unwrap(),
+            "0x70997970c51812dc3a010c7d01b50e0d17dc79c8"
+        );
+    }
+
+    #[test]
+    fn test_generate_key_base() {
+        let mnemonic = generate_mnemonic(24).unwrap();
+        assert_eq!(mnemonic.split(' ').count(), 24);
+        assert!(generate_mnemonic(13).is_err());
+        assert!(private_key_from_mnemonic(&mnemonic, "", DEFAULT_DERIVATION_PATH).is_ok());
+
+        let private_key = generate_private_key();
+        let public_key_base = compute_public_key_base(&hex::encode(private_key)).unwrap();
+        assert_eq!(public_key_base.len(), 128);
+
+        assert!(parse_derivation_path("44'/60'").is_err());
+        assert!(parse_derivation_path("m/2147483648").is_err());
+        assert_eq!(
+            parse_derivation_path("m/44'/0").unwrap(),
+            vec![44 + HARDENED_OFFSET, 0]
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use aes::cipher::{KeyIvInit, StreamCipher};
+use rand::Rng;
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// Same parameters as geth uses for standard keystore files
+pub const DEFAULT_SCRYPT_LOG_N: u8 = 18;
+pub const DEFAULT_SCRYPT_R: u32 = 8;
+pub const DEFAULT_SCRYPT_P: u32 = 1;
+
+const DKLEN: u32 = 32;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kdf", content = "kdfparams", rename_all = "lowercase")]
+pub enum KdfParams {
+    Scrypt {
+        dklen: u32,
+        n: u32,
+        p: u32,
+        r: u32,
+        salt: String,
+    },
+    Pbkdf2 {
+        c: u32,
+        dklen: u32,
+        prf: String,
+        salt: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CipherParams {
+    pub iv: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KeystoreCrypto {
+    pub cipher: String,
+    pub cipherparams: CipherParams,
+    pub ciphertext: String,
+    #[serde(flatten)]
+    pub kdf: KdfParams,
+    pub mac: String,
+}
+
+/// Web3 Secret Storage (keystore v3) file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KeystoreV3 {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub crypto: KeystoreCrypto,
+    pub id: String,
+    pub version: u32,
+}
+
+fn decode_param(name: &str, value: &str) -> Result<Vec<u8>, AddressologyError> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| err_custom_create!("Failed to decode keystore {name}: {e}"))
+}
+
+fn derive_key(kdf: &KdfParams, password: &str) -> Result<Vec<u8>, AddressologyError> {
+    match kdf {
+        KdfParams::Scrypt {
+            dklen,
+            n,
+            p,
+            r,
+            salt,
+        } => {
+            if !n.is_power_of_two() {
+                return Err(err_custom_create!(
+                    "Scrypt n has to be power of two, got {n}"
+                ));
+            }
+            let params = scrypt::Params::new(n.trailing_zeros() as u8, *r, *p, *dklen as usize)
+                .map_err(|e| err_custom_create!("Invalid scrypt params: {e}"))?;
+            let mut key = vec![0u8; *dklen as usize];
+            scrypt::scrypt(
+                password.as_bytes(),
+                &decode_param("salt", salt)?,
+                &params,
+                &mut key,
+            )
+            .map_err(|e| err_custom_create!("Scrypt failed: {e}"))?;
+            Ok(key)
+        }
+        KdfParams::Pbkdf2 {
+            c,
+            dklen,
+            prf,
+            salt,
+        } => {
+            if prf != "hmac-sha256" {
+                return Err(err_custom_create!("Unsupported pbkdf2 prf {prf}"));
+            }
+            let mut key = vec![0u8; *dklen as usize];
+            pbkdf2::pbkdf2_hmac::<sha2::Sha256>(
+                password.as_bytes(),
+                &decode_param("salt", salt)?,
+                *c,
+                &mut key,
+            );
+            Ok(key)
+        }
+    }
+}
+
+fn keystore_mac(derived_key: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(&derived_key[16..32]);
+    hasher.update(ciphertext);
+    let mut mac = [0u8; 32];
+    hasher.finalize(&mut mac);
+    mac
+}
+
+pub fn private_key_to_address(private_key: &[u8]) -> Result<String, AddressologyError> {
+    let secret_key = SecretKey::from_slice(private_key)
+        .map_err(|e| err_custom_create!("Failed to decode private key: {e}"))?;
+    let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &secret_key);
+    let mut hasher = Keccak::v256();
+    hasher.update(&public_key.serialize_uncompressed()[1..]);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    Ok(format!("0x{}", hex::encode(&hash[12..])))
+}
+
+pub fn encrypt_keystore_with_params(
+    private_key: &[u8],
+    password: &str,
+    log_n: u8,
+    r: u32,
+    p: u32,
+) -> Result<KeystoreV3, AddressologyError> {
+    let address = private_key_to_address(private_key)?;
+
+    let mut rng = rand::rng();
+    let salt: [u8; 32] = rng.random();
+    let iv: [u8; 16] = rng.random();
+
+    let kdf = KdfParams::Scrypt {
+        dklen: DKLEN,
+        n: 1 << log_n,
+        p,
+        r,
+        salt: hex::encode(salt),
+    };
+    let derived_key = derive_key(&kdf, password)?;
+
+    let mut ciphertext = private_key.to_vec();
+    Aes128Ctr::new(derived_key[0..16].into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+    Ok(KeystoreV3 {
+        address: Some(address.trim_start_matches("0x").to_string()),
+        crypto: KeystoreCrypto {
+            cipher: "aes-128-ctr".to_string(),
+            cipherparams: CipherParams {
+                iv: hex::encode(iv),
+            },
+            ciphertext: hex::encode(&ciphertext),
+            kdf,
+            mac: hex::encode(keystore_mac(&derived_key, &ciphertext)),
+        },
+        id: uuid::Uuid::new_v4().to_string(),
+        version: 3,
+    })
+}
+
+pub fn encrypt_keystore(
+    private_key: &[u8],
+    password: &str,
+) -> Result<KeystoreV3, AddressologyError> {
+    encrypt_keystore_with_params(
+        private_key,
+        password,
+        DEFAULT_SCRYPT_LOG_N,
+        DEFAULT_SCRYPT_R,
+        DEFAULT_SCRYPT_P,
+    )
+}
+
+pub fn decrypt_keystore(
+    keystore: &KeystoreV3,
+    password: &str,
+) -> Result<Vec<u8>, AddressologyError> {
+    if keystore.version != 3 {
+        return Err(err_custom_create!(
+            "Unsupported keystore version {}",
+            keystore.version
+        ));
+    }
+    if keystore.crypto.cipher != "aes-128-ctr" {
+        return Err(err_custom_create!(
+            "Unsupported keystore cipher {}",
+            keystore.crypto.cipher
+        ));
+    }
+    let derived_key = derive_key(&keystore.crypto.kdf, password)?;
+    if derived_key.len() < 32 {
+        return Err(err_custom_create!("Keystore dklen has to be at least 32"));
+    }
+    let ciphertext = decode_param("ciphertext", &keystore.crypto.ciphertext)?;
+    let mac = decode_param("mac", &keystore.crypto.mac)?;
+    if keystore_mac(&derived_key, &ciphertext).as_slice() != mac.as_slice() {
+        return Err(err_custom_create!("Keystore MAC mismatch, wrong password?"));
+    }
+    let iv: [u8; 16] = decode_param("iv", &keystore.crypto.cipherparams.iv)?
+        .try_into()
+        .map_err(|_| err_custom_create!("Keystore iv has to be 16 bytes"))?;
+
+    let mut private_key = ciphertext;
+    Aes128Ctr::new(derived_key[0..16].into(), &iv.into()).apply_keystream(&mut private_key);
+    Ok(private_key)
+}
+
+pub fn save_keystore(keystore: &KeystoreV3, path: &str) -> Result<(), AddressologyError> {
+    let json = serde_json::to_string_pretty(keystore)
+        .map_err(|e| err_custom_create!("Failed to serialize keystore: {e}"))?;
+    std::fs::write(path, json)
+        .map_err(|e| err_custom_create!("Failed to write keystore {path}: {e}"))
+}
+
+pub fn load_keystore(path: &str) -> Result<KeystoreV3, AddressologyError> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| err_custom_create!("Failed to read keystore {path}: {e}"))?;
+    serde_json::from_str(&json).map_err(|e| err_custom_create!("Invalid keystore {path}: {e}"))
+}
+
+/// Passphrase from KEYSTORE_PASSWORD env, otherwise prompted on terminal without echo.
+/// New keystore asks for passphrase twice, typo would lock the key away
+pub fn read_keystore_password(confirm: bool) -> Result<String, AddressologyError> {
+    let password = match std::env::var("KEYSTORE_PASSWORD") {
+        Ok(password) => password,
+        Err(_) => {
+            let password = rpassword::prompt_password("Enter keystore passphrase: ")
+                .map_err(|e| err_custom_create!("Failed to read passphrase: {e}"))?;
+            if confirm {
+                let repeated = rpassword::prompt_password("Repeat keystore passphrase: ")
+                    .map_err(|e| err_custom_create!("Failed to read passphrase: {e}"))?;
+                if repeated != password {
+                    return Err(err_custom_create!("Keystore passphrases do not match"));
+                }
+            }
+            password
+        }
+    };
+    if password.is_empty() {
+        return Err(err_custom_create!("Keystore passphrase cannot be empty"));
+    }
+    Ok(password)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test vectors from Web3 Secret Storage Definition
+    const PRIVATE_KEY: &str = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    #[test]
+    fn test_decrypt_pbkdf2_vector() {
+        let keystore: KeystoreV3 = serde_json::from_str(
+            r#"{
+                "crypto": {
+                    "cipher": "aes-128-ctr",
+                    "cipherparams": {"iv": "6087dab2f9fdbbfaddc31a909735c1e6"},
+                    "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                    "kdf": "pbkdf2",
+                    "kdfparams": {
+                        "c": 262144,
+                        "dklen": 32,
+                        "prf": "hmac-sha256",
+                        "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                    },
+                    "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+                },
+                "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+                "version": 3
+            }"#,
+        )
+        .unwrap();
+        let private_key = decrypt_keystore(&keystore, "testpassword").unwrap();
+        assert_eq!(hex::encode(private_key), PRIVATE_KEY);
+        assert!(decrypt_keystore(&keystore, "wrongpassword").is_err());
+    }
+
+    #[test]
+    fn test_scrypt_kdf() {
+        // RFC 7914 test vector
+        let kdf = KdfParams::Scrypt {
+            dklen: 64,
+            n: 1024,
+            p: 16,
+            r: 8,
+            salt: hex::encode("NaCl"),
+        };
+        assert_eq!(
+            hex::encode(derive_key(&kdf, "password").unwrap()),
+            "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+             2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+        );
+    }
+
+    #[test]
+    fn test_keystore_roundtrip() {
+        let private_key = hex::decode(PRIVATE_KEY).unwrap();
+        // light scrypt params to keep the test fast
+        let keystore = encrypt_keystore_with_params(&private_key, "secret", 10, 8, 1).unwrap();
+        assert_eq!(
+            keystore.address.as_deref().map(|a| format!("0x{a}")),
+            Some(private_key_to_address(&private_key).unwrap())
+        );
+
+        let json = serde_json::to_string(&keystore).unwrap();
+        assert!(json.contains(r#""kdf":"scrypt""#));
+        let keystore: KeystoreV3 = serde_json::from_str(&json).unwrap();
+        assert_eq!(decrypt_keystore(&keystore, "secret").unwrap(), private_key);
+        assert!(decrypt_keystore(&keystore, "Secret").is_err());
+
+        let path = std::env::temp_dir().join(format!("keystore-{}.json", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        save_keystore(&keystore, path).unwrap();
+        assert_eq!(load_keystore(path).unwrap(), keystore);
+        std::fs::remove_file(path).unwrap();
+        assert!(load_keystore(path).is_err());
+    }
+}
@@ -3,10 +3,16 @@
 #![allow(clippy::useless_format)]
 
 mod api;
+mod benchmark;
 mod config;
+mod db;
 mod error;
 mod fancy;
 mod hash;
+mod keygen;
+mod keystore;
+mod result_queue;
+mod runner_history;
 
 pub mod runner;
 pub mod service;
@@ -15,16 +21,40 @@ mod update;
 
 use crate::api::scope::server_api_scope;
 use std::collections::BTreeMap;
+use std::str::FromStr;
 
-use crate::config::initialize_config;
-use crate::hash::{compute_address_command, compute_create3_command};
-use crate::runner::CrunchRunner;
+use crate::benchmark::{run_benchmarks, BenchmarkRequest};
+use crate::config::{
+    get_result_queue_memory_cap, get_result_queue_resume_interval_secs, get_result_queue_spill_dir,
+    get_result_sink_capacity, get_runner_history_dir, get_runner_history_interval_secs,
+    initialize_config,
+};
+use crate::db::benchmark::{compare_benchmark_report, list_benchmark_reports};
+use crate::db::connection::create_sqlite_connection;
+use crate::db::export::{export_header, export_next_batch, parse_export_columns, ExportFormat};
+use crate::db::import::{parse_import_input, store_import, verify_import, ImportFormat};
+use crate::db::model::{FancyQuery, FancySortBy};
+use crate::db::retention::{retention_loop, RetentionPolicy, RetentionStats};
+use crate::db::store::store_results;
+use crate::error::AddressologyError;
+use crate::fancy::{
+    calibrate_scores, estimate_time_to_find, explain_score, parse_verify_input, verify_records,
+    EstimateTarget, FancyScoreCategory,
+};
+use crate::hash::{
+    combine_private_key, compute_address_command, compute_create2, compute_create3,
+    compute_init_code_hash, compute_public_key_base, Create3Scheme,
+};
+use crate::result_queue::ResultQueue;
+use crate::runner::{resume_drained_loop, CrunchRunner, WorkTarget};
+use crate::runner_history::{runner_history_loop, runner_history_path, RunnerHistory};
 use crate::service::provider::{
     test_run_provider, ProviderCommand, ProviderRunner, ProviderRunnerData, ProviderSettings,
 };
 use crate::service::yagna::{
     TrackingResults, YagnaCommand, YagnaNetType, YagnaRunner, YagnaRunnerData, YagnaSettings,
 };
+use crate::types::DbAddress;
 use actix_multipart::form::MultipartFormConfig;
 use actix_multipart::MultipartError;
 use actix_web::http::StatusCode;
@@ -33,7 +63,9 @@ use awc::Client;
 use clap::{Parser, Subcommand};
 use lazy_static::lazy_static;
 use serde::Deserialize;
+use sqlx::SqlitePool;
 use std::env;
+use std::io::{BufWriter, Read, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -69,6 +101,9 @@ pub struct ServerData {
     pub yagna_runner: Arc<tokio::sync::Mutex<YagnaRunner>>,
     pub provider_runner: Arc<tokio::sync::Mutex<ProviderRunner>>,
     pub activity_tracking_results: Arc<parking_lot::Mutex<TrackingResults>>,
+    pub db: SqlitePool,
+    pub retention_stats: Arc<parking_lot::Mutex<RetentionStats>>,
+    pub runner_history: Arc<parking_lot::Mutex<Vec<RunnerHistory>>>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -218,6 +253,25 @@ enum Commands {
         factory: String,
         #[arg(short, long)]
         salt: String,
+        /// Proxy scheme, plain salt (solady) when not given
+        #[arg(long)]
+        scheme: Option<Create3Scheme>,
+        /// Address calling the factory, needed by schemes deriving salt from it
+        #[arg(short, long)]
+        caller: Option<String>,
+        /// Chain id, needed for CreateX salts with cross-chain redeploy protection
+        #[arg(long)]
+        chain_id: Option<u64>,
+    },
+    ComputeCreate2 {
+        #[arg(short, long)]
+        deployer: String,
+        #[arg(short, long)]
+        salt: String,
+        #[arg(short, long, conflicts_with = "init_code_hash")]
+        init_code: Option<String>,
+        #[arg(long, required_unless_present = "init_code")]
+        init_code_hash: Option<String>,
     },
     ComputeAddress {
         #[arg(short = 'b', long)]
@@ -227,6 +281,157 @@ enum Commands {
         #[arg(short = 'e', long)]
         expected_address: Option<String>,
     },
+    /// Show score breakdown and price of the address
+    Score {
+        address: String,
+        /// Print breakdown as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Estimate time to find address with given pattern at given speed
+    Estimate {
+        /// Score category, like leading_zeroes
+        #[arg(short, long, requires = "min_score", conflicts_with_all = ["prefix", "difficulty"])]
+        category: Option<String>,
+        #[arg(short, long)]
+        min_score: Option<f64>,
+        /// Hex prefix, ? matches any character
+        #[arg(short, long, conflicts_with = "difficulty")]
+        prefix: Option<String>,
+        #[arg(short, long)]
+        difficulty: Option<f64>,
+        /// Aggregate speed in MH/s
+        #[arg(short, long)]
+        speed: f64,
+    },
+    /// Recompute and re-score addresses from CSV or JSONL file (- for stdin)
+    Verify {
+        input: String,
+        #[arg(short, long)]
+        threads: Option<usize>,
+        /// Print report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compare score difficulty formulas with tail probabilities of random addresses
+    Calibrate {
+        #[arg(short, long, default_value = "1000000")]
+        samples: u64,
+        #[arg(short, long)]
+        threads: Option<usize>,
+        #[arg(long, default_value = "1")]
+        seed: u64,
+        /// Allowed relative overstatement of rarity before calibration fails
+        #[arg(long, default_value = "0.25")]
+        tolerance: f64,
+        /// Print report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Generate key pair for split-key order, private part is saved to encrypted keystore
+    GenerateKeyBase {
+        /// Keystore file (passphrase from KEYSTORE_PASSWORD or prompt)
+        #[arg(short = 'k', long, default_value = "key-base.json")]
+        keystore: String,
+        /// Derive key from newly generated mnemonic instead of random key
+        #[arg(long)]
+        mnemonic: bool,
+        #[arg(long, default_value = "12", requires = "mnemonic")]
+        words: usize,
+        #[arg(long, default_value = keygen::DEFAULT_DERIVATION_PATH, requires = "mnemonic")]
+        derivation_path: String,
+    },
+    /// Combine base private key with found private key add of split-key vanity address
+    CombineKey {
+        /// Keystore with base private key written by GenerateKeyBase
+        /// (passphrase from KEYSTORE_PASSWORD or prompt)
+        #[arg(long, default_value = "key-base.json")]
+        key_base_keystore: String,
+        /// File with base private key in hex instead of keystore, - for stdin
+        #[arg(short = 'k', long)]
+        private_key_base_file: Option<String>,
+        #[arg(short = 'p', long)]
+        private_key_add: String,
+        #[arg(short = 'e', long)]
+        expected_address: Option<String>,
+        /// Save final key as encrypted keystore v3 file (passphrase from KEYSTORE_PASSWORD or prompt),
+        /// only address and keystore path are printed then
+        #[arg(long)]
+        keystore: Option<String>,
+    },
+
+    /// Verify, re-score and store results from jsonl, csv or raw salt dumps
+    Import {
+        /// Input file, - for stdin
+        input: String,
+        /// jsonl, csv or raw, guessed from content when not given (raw has to be explicit)
+        #[arg(short, long)]
+        format: Option<String>,
+        /// WorkTarget as json, like {"factory":"0x..."}, required for raw salts
+        #[arg(long)]
+        target: Option<String>,
+        #[arg(short, long)]
+        threads: Option<usize>,
+        /// Print report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export stored addresses as csv or jsonl
+    Export {
+        #[arg(short, long, default_value = "csv")]
+        format: String,
+        /// Comma separated columns, like address,addressMixedCase,scores,price
+        #[arg(short, long, default_value = "")]
+        columns: String,
+        /// Output file, stdout when not given
+        #[arg(short, long)]
+        output: Option<String>,
+        #[arg(long)]
+        category: Option<String>,
+        #[arg(long)]
+        min_score: Option<f64>,
+        #[arg(long)]
+        max_score: Option<f64>,
+        #[arg(long)]
+        owner: Option<String>,
+        #[arg(long)]
+        job: Option<String>,
+        /// Regex on lowercase address
+        #[arg(long)]
+        regex: Option<String>,
+        #[arg(long, default_value = "score")]
+        sort: String,
+        #[arg(long)]
+        asc: bool,
+    },
+    /// Benchmark cuda runners and compare with previous stored reports
+    Benchmark {
+        /// Number of cuda devices, all of them are benchmarked unless --runner is given
+        #[arg(long, default_value = "1")]
+        no_cuda_devices: u64,
+        /// Runner number to benchmark, can be repeated
+        #[arg(short, long)]
+        runner: Vec<usize>,
+        #[arg(short, long, default_value = "60")]
+        duration: f64,
+        #[arg(long, default_value = "1")]
+        sample_interval: f64,
+        /// WorkTarget as json, like {"factory":"0x..."}
+        #[arg(long)]
+        target: Option<String>,
+        /// Driver or cruncher version stored with report
+        #[arg(long)]
+        tool_version: Option<String>,
+        /// Only list stored reports
+        #[arg(long)]
+        list: bool,
+        /// List reports of one device
+        #[arg(long)]
+        device: Option<String>,
+        /// Print reports as JSON
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Start web server
     Server {
@@ -256,6 +461,117 @@ fn handle_multipart_error(err: MultipartError, _req: &HttpRequest) -> actix_web:
     actix_web::Error::from(err)
 }
 
+fn generate_key_base(
+    keystore_path: &str,
+    mnemonic: Option<(usize, String)>,
+) -> Result<String, AddressologyError> {
+    if PathBuf::from(keystore_path).exists() {
+        return Err(err_custom_create!(
+            "Keystore {} already exists, refusing to overwrite",
+            keystore_path
+        ));
+    }
+    let private_key = if let Some((words, derivation_path)) = mnemonic {
+        let phrase = keygen::generate_mnemonic(words)?;
+        // mnemonic is the only backup of the key besides the keystore, so show it once
+        eprintln!("Mnemonic (write it down): {}", phrase);
+        eprintln!("Derivation path: {}", derivation_path);
+        keygen::private_key_from_mnemonic(&phrase, "", &derivation_path)?
+    } else {
+        keygen::generate_private_key()
+    };
+
+    let password = keystore::read_keystore_password(true)?;
+    let keystore = keystore::encrypt_keystore(&private_key, &password)?;
+    keystore::save_keystore(&keystore, keystore_path)?;
+    log::info!(
+        "Saved base key of address 0x{} to {}",
+        keystore.address.clone().unwrap_or_default(),
+        keystore_path
+    );
+    compute_public_key_base(&hex::encode(private_key))
+}
+
+/// Secret hex value from file or stdin (-), surrounding whitespace is ignored
+fn read_secret_input(path: &str) -> Result<String, AddressologyError> {
+    let content = if path == "-" {
+        eprintln!("Enter base private key:");
+        let mut content = String::new();
+        std::io::stdin()
+            .read_line(&mut content)
+            .map_err(|e| err_custom_create!("Failed to read base private key: {e}"))?;
+        content
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|e| err_custom_create!("Failed to read base private key {path}: {e}"))?
+    };
+    let secret = content.trim().to_string();
+    if secret.is_empty() {
+        return Err(err_custom_create!("Base private key is empty"));
+    }
+    Ok(secret)
+}
+
+fn read_key_base_keystore(path: &str) -> Result<String, AddressologyError> {
+    let keystore = keystore::load_keystore(path)?;
+    let password = keystore::read_keystore_password(false)?;
+    let private_key = keystore::decrypt_keystore(&keystore, &password)?;
+    Ok(format!("0x{}", hex::encode(private_key)))
+}
+
+/// Combined private key, or address and keystore path when the key is saved to keystore
+fn combine_key(
+    private_key_base: &str,
+    private_key_add: &str,
+    expected_address: Option<&str>,
+    keystore_path: Option<&str>,
+) -> Result<String, AddressologyError> {
+    let private_key = combine_private_key(private_key_base, private_key_add)?;
+    let private_key_bytes = hex::decode(private_key.trim_start_matches("0x"))
+        .map_err(|e| err_custom_create!("Failed to decode combined key: {e}"))?;
+    let address = keystore::private_key_to_address(&private_key_bytes)?;
+
+    // the same address has to come out of the public key path used when the order was crunched
+    let public_key_base = compute_public_key_base(private_key_base)?;
+    let split_key_address = compute_address_command(&public_key_base, private_key_add)?;
+    if split_key_address != address {
+        return Err(err_custom_create!(
+            "Combined key address {} does not match split key address {}",
+            address,
+            split_key_address
+        ));
+    }
+    if let Some(expected) = expected_address {
+        if address.to_lowercase()
+            != format!("0x{}", expected.trim_start_matches("0x")).to_lowercase()
+        {
+            return Err(err_custom_create!(
+                "Combined key address: {} does not match expected: {}",
+                address,
+                expected
+            ));
+        }
+        log::info!("Combined key address: {} matches expected", address);
+    } else {
+        log::info!("Combined key address: {}", address);
+    }
+
+    if let Some(path) = keystore_path {
+        let password = keystore::read_keystore_password(true)?;
+        let keystore = keystore::encrypt_keystore(&private_key_bytes, &password)?;
+        if keystore::decrypt_keystore(&keystore, &password)? != private_key_bytes {
+            return Err(err_custom_create!(
+                "Keystore does not decrypt to combined key"
+            ));
+        }
+        keystore::save_keystore(&keystore, path)?;
+        log::info!("Saved keystore to {}", path);
+        // key is safe in keystore, do not leak it to terminal or shell history
+        return Ok(format!("{} {}", address, path));
+    }
+    Ok(private_key)
+}
+
 #[actix_rt::main]
 async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
@@ -271,7 +587,7 @@ async fn main() -> std::io::Result<()> {
 
     let args = Cli::parse();
 
-    initialize_config();
+    initialize_config().map_err(|err| std::io::Error::other(err.to_string()))?;
 
     let conf = config::get_config();
 
@@ -306,16 +622,69 @@ async fn main() -> std::io::Result<()> {
                 yagna_settings.to_owned(),
             );
 
+            let conn = create_sqlite_connection(Some(&PathBuf::from(&args.db)), true)
+                .await
+                .map_err(|err| std::io::Error::other(err.to_string()))?;
+            let (result_sender, result_receiver) =
+                tokio::sync::mpsc::channel(get_result_sink_capacity());
+            tokio::spawn(store_results(conn.clone(), result_receiver));
+            let retention_stats = Arc::new(parking_lot::Mutex::new(RetentionStats::default()));
+            if conf.retention.enabled {
+                match RetentionPolicy::from_config(&conf.retention) {
+                    Ok(policy) => {
+                        tokio::spawn(retention_loop(
+                            conn.clone(),
+                            policy,
+                            retention_stats.clone(),
+                        ));
+                    }
+                    Err(err) => log::error!("Invalid retention config, retention disabled: {err}"),
+                }
+            }
+
             let mut cuda_workers = Vec::new();
             if let Some(no_cuda_devices) = no_cuda_devices {
                 for i in 0..no_cuda_devices {
-                    cuda_workers.push(Arc::new(tokio::sync::Mutex::new(CrunchRunner::new(
-                        "profanity_cuda.exe".parse().unwrap(),
-                        i,
-                    ))));
+                    let mut runner = CrunchRunner::new("profanity_cuda.exe".parse().unwrap(), i);
+                    runner.set_result_sink(result_sender.clone());
+                    let spill_path = PathBuf::from(get_result_queue_spill_dir())
+                        .join(format!("runner-{i}.jsonl"));
+                    match ResultQueue::with_spill(&spill_path, get_result_queue_memory_cap()) {
+                        Ok(queue) => runner.set_result_queue(queue),
+                        Err(err) => {
+                            log::error!("Result queue of runner {i} is kept only in memory: {err}")
+                        }
+                    }
+                    cuda_workers.push(Arc::new(tokio::sync::Mutex::new(runner)));
                 }
             }
 
+            tokio::spawn(resume_drained_loop(
+                cuda_workers.clone(),
+                get_result_queue_resume_interval_secs(),
+            ));
+
+            let history_interval = get_runner_history_interval_secs();
+            let history_dir = get_runner_history_dir().map(PathBuf::from);
+            // raw samples cover last hour, older data is kept only in buckets
+            let raw_cap = (3600 / history_interval) as usize;
+            let runner_history = Arc::new(parking_lot::Mutex::new(
+                (0..cuda_workers.len())
+                    .map(|i| match &history_dir {
+                        Some(dir) => {
+                            RunnerHistory::load_or_new(&runner_history_path(dir, i), raw_cap)
+                        }
+                        None => RunnerHistory::new(raw_cap),
+                    })
+                    .collect::<Vec<_>>(),
+            ));
+            tokio::spawn(runner_history_loop(
+                cuda_workers.clone(),
+                runner_history.clone(),
+                history_interval,
+                history_dir,
+            ));
+
             let activity_tracking_results = Arc::new(parking_lot::Mutex::new(TrackingResults {
                 actvities: BTreeMap::new(),
             }));
@@ -345,6 +714,9 @@ async fn main() -> std::io::Result<()> {
                     yagna_runner: yagna_runner.clone(),
                     provider_runner: provider_runner.clone(),
                     activity_tracking_results: activity_tracking_results.clone(),
+                    db: conn.clone(),
+                    retention_stats: retention_stats.clone(),
+                    runner_history: runner_history.clone(),
                 }));
 
                 App::new()
@@ -369,8 +741,23 @@ async fn main() -> std::io::Result<()> {
             .await
         }
 
-        Commands::ComputeCreate3 { factory, salt } => {
-            let result = compute_create3_command(&factory, &salt);
+        Commands::ComputeCreate3 {
+            factory,
+            salt,
+            scheme,
+            caller,
+            chain_id,
+        } => {
+            let scheme = scheme.unwrap_or_else(|| {
+                if let Some(known) = Create3Scheme::of_known_factory(&factory) {
+                    log::warn!(
+                        "Factory {factory} is known {known} factory, \
+                        pass --scheme {known} to use its salt derivation"
+                    );
+                }
+                Create3Scheme::Solady
+            });
+            let result = compute_create3(&factory, &salt, scheme, caller.as_deref(), chain_id);
             match result {
                 Ok(hash) => {
                     log::info!("Computed create3 hash: {}", hash);
@@ -383,6 +770,31 @@ async fn main() -> std::io::Result<()> {
             }
             Ok(())
         }
+        Commands::ComputeCreate2 {
+            deployer,
+            salt,
+            init_code,
+            init_code_hash,
+        } => {
+            let init_code_hash = match (init_code, init_code_hash) {
+                (Some(init_code), _) => compute_init_code_hash(&init_code),
+                (None, Some(init_code_hash)) => Ok(init_code_hash),
+                (None, None) => unreachable!("clap requires init code or its hash"),
+            };
+            let result = init_code_hash
+                .and_then(|init_code_hash| compute_create2(&deployer, &salt, &init_code_hash));
+            match result {
+                Ok(address) => {
+                    log::info!("Computed create2 address: {}", address);
+                    println!("{}", address);
+                }
+                Err(e) => {
+                    log::error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            Ok(())
+        }
         Commands::ComputeAddress {
             public_key_base,
             private_key_add,
@@ -423,6 +835,450 @@ async fn main() -> std::io::Result<()> {
             Ok(())
         }
 
+        Commands::Score { address, json } => {
+            let address = match DbAddress::from_str(&address) {
+                Ok(address) => address,
+                Err(e) => {
+                    log::error!("Invalid address {}: {}", address, e);
+                    std::process::exit(1);
+                }
+            };
+            let explanation = explain_score(address.addr());
+            if json {
+                println!("{}", serde_json::to_string_pretty(&explanation)?);
+            } else {
+                println!("{}", explanation.address);
+                for entry in &explanation.entries {
+                    println!(
+                        "{} {:<24} score: {:<6} 1 in {:.0}",
+                        if entry.winning { "*" } else { " " },
+                        entry.name,
+                        entry.score,
+                        entry.difficulty
+                    );
+                }
+                println!(
+                    "Category: {}, price multiplier: {:.2}, price: {}",
+                    explanation.category_name, explanation.price_multiplier, explanation.price
+                );
+                println!("{}", explanation.explanation);
+            }
+            Ok(())
+        }
+        Commands::Estimate {
+            category,
+            min_score,
+            prefix,
+            difficulty,
+            speed,
+        } => {
+            let target = match (category, prefix, difficulty) {
+                (Some(category), _, _) => match FancyScoreCategory::from_str(&category) {
+                    Ok(category) => EstimateTarget::Category {
+                        category,
+                        min_score: min_score.unwrap_or_default(),
+                    },
+                    Err(_) => {
+                        log::error!("Unknown score category {}", category);
+                        std::process::exit(1);
+                    }
+                },
+                (None, Some(prefix), _) => EstimateTarget::Prefix(prefix),
+                (None, None, Some(difficulty)) => EstimateTarget::Difficulty(difficulty),
+                (None, None, None) => {
+                    log::error!("One of category, prefix or difficulty is required");
+                    std::process::exit(1);
+                }
+            };
+            match estimate_time_to_find(&target, speed) {
+                Ok(estimate) => {
+                    println!("Difficulty: 1 in {:.0}", estimate.difficulty);
+                    println!("Expected time: {:.1} s", estimate.expected_seconds);
+                    println!(
+                        "P50/P90/P99: {:.1} s / {:.1} s / {:.1} s",
+                        estimate.p50_seconds, estimate.p90_seconds, estimate.p99_seconds
+                    );
+                    println!("Expected hits per day: {:.2}", estimate.hits_per_day);
+                }
+                Err(e) => {
+                    log::error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            Ok(())
+        }
+        Commands::Import {
+            input,
+            format,
+            target,
+            threads,
+            json,
+        } => {
+            let to_io_error =
+                |err: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, err);
+            let format = format
+                .map(|format| ImportFormat::from_str(&format))
+                .transpose()
+                .map_err(to_io_error)?;
+            let target = target
+                .map(|target| serde_json::from_str::<WorkTarget>(&target))
+                .transpose()?;
+            let content = if input == "-" {
+                let mut content = Vec::new();
+                std::io::stdin().read_to_end(&mut content)?;
+                content
+            } else {
+                std::fs::read(&input)?
+            };
+            let threads = threads.unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            });
+            let records =
+                parse_import_input(&content, format).map_err(|e| to_io_error(e.to_string()))?;
+            log::info!(
+                "Importing {} records using {} threads",
+                records.len(),
+                threads
+            );
+            let (verified, mut report) = verify_import(&records, target.as_ref(), threads);
+            let conn = create_sqlite_connection(Some(&PathBuf::from(&args.db)), true)
+                .await
+                .map_err(|err| std::io::Error::other(err.to_string()))?;
+            store_import(&conn, &verified, &mut report)
+                .await
+                .map_err(|err| std::io::Error::other(err.to_string()))?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                for rejected in &report.rejected {
+                    println!(
+                        "line {}: address {} computed {} {}",
+                        rejected.line,
+                        rejected.address.as_deref().unwrap_or("-"),
+                        rejected.computed.as_deref().unwrap_or("-"),
+                        rejected.error.as_deref().unwrap_or("")
+                    );
+                }
+                for (category, count) in &report.categories {
+                    println!("{}: {}", category, count);
+                }
+                println!(
+                    "Verified {}/{}, rejected: {}, duplicates: {}, inserted: {}, created updated: {}, already stored: {}",
+                    report.verified,
+                    report.total,
+                    report.rejected.len(),
+                    report.duplicates,
+                    report.inserted,
+                    report.updated_created,
+                    report.already_stored
+                );
+            }
+            Ok(())
+        }
+        Commands::Export {
+            format,
+            columns,
+            output,
+            category,
+            min_score,
+            max_score,
+            owner,
+            job,
+            regex,
+            sort,
+            asc,
+        } => {
+            let to_io_error =
+                |err: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, err);
+            let format = ExportFormat::from_str(&format).map_err(to_io_error)?;
+            let columns = parse_export_columns(&columns).map_err(|e| to_io_error(e.to_string()))?;
+            let mut query = FancyQuery {
+                category,
+                min_score,
+                max_score,
+                owner,
+                job,
+                address_regex: regex,
+                sort_by: FancySortBy::from_str(&sort).map_err(to_io_error)?,
+                ascending: asc,
+                ..Default::default()
+            };
+            let conn = create_sqlite_connection(Some(&PathBuf::from(&args.db)), true)
+                .await
+                .map_err(|err| std::io::Error::other(err.to_string()))?;
+            let mut writer: Box<dyn Write> = match &output {
+                Some(output) => Box::new(BufWriter::new(std::fs::File::create(output)?)),
+                None => Box::new(std::io::stdout().lock()),
+            };
+            writer.write_all(export_header(format, &columns).as_bytes())?;
+            let mut batches = 0;
+            while let Some(batch) = export_next_batch(&conn, &mut query, format, &columns)
+                .await
+                .map_err(|err| std::io::Error::other(err.to_string()))?
+            {
+                writer.write_all(batch.as_bytes())?;
+                batches += 1;
+            }
+            writer.flush()?;
+            log::info!("Export finished, {} batches written", batches);
+            Ok(())
+        }
+        Commands::Verify {
+            input,
+            threads,
+            json,
+        } => {
+            let content = if input == "-" {
+                std::io::read_to_string(std::io::stdin())?
+            } else {
+                std::fs::read_to_string(&input)?
+            };
+            let threads = threads.unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            });
+            let records = parse_verify_input(&content).map_err(|err| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, err.to_string())
+            })?;
+            log::info!(
+                "Verifying {} records using {} threads",
+                records.len(),
+                threads
+            );
+            let report = verify_records(&records, threads);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                for mismatch in &report.mismatches {
+                    println!(
+                        "line {}: address {} computed {} {}",
+                        mismatch.line,
+                        mismatch.address.as_deref().unwrap_or("-"),
+                        mismatch.computed.as_deref().unwrap_or("-"),
+                        mismatch.error.as_deref().unwrap_or("")
+                    );
+                }
+                for (category, count) in &report.categories {
+                    println!("{}: {}", category, count);
+                }
+                println!(
+                    "Verified {}/{}, mismatches: {}",
+                    report.verified,
+                    report.total,
+                    report.mismatches.len()
+                );
+            }
+            if !report.is_ok() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Commands::Benchmark {
+            no_cuda_devices,
+            runner,
+            duration,
+            sample_interval,
+            target,
+            tool_version,
+            list,
+            device,
+            json,
+        } => {
+            let conn = create_sqlite_connection(Some(&PathBuf::from(&args.db)), true)
+                .await
+                .map_err(|err| std::io::Error::other(err.to_string()))?;
+            let reports = if list {
+                let mut reports = Vec::new();
+                for report in list_benchmark_reports(&conn, device.as_deref(), 100)
+                    .await
+                    .map_err(|err| std::io::Error::other(err.to_string()))?
+                {
+                    reports.push(
+                        compare_benchmark_report(&conn, report)
+                            .await
+                            .map_err(|err| std::io::Error::other(err.to_string()))?,
+                    );
+                }
+                reports
+            } else {
+                let request = BenchmarkRequest {
+                    runners: (!runner.is_empty()).then_some(runner),
+                    duration_secs: duration,
+                    sample_interval_secs: sample_interval,
+                    target: target
+                        .map(|target| serde_json::from_str::<WorkTarget>(&target))
+                        .transpose()?,
+                    tool_version,
+                };
+                let runners = (0..no_cuda_devices)
+                    .map(|i| {
+                        Arc::new(tokio::sync::Mutex::new(CrunchRunner::new(
+                            "profanity_cuda.exe".parse().unwrap(),
+                            i,
+                        )))
+                    })
+                    .collect::<Vec<_>>();
+                let outcomes = run_benchmarks(&conn, &runners, &request)
+                    .await
+                    .map_err(|err| std::io::Error::other(err.to_string()))?;
+                for outcome in outcomes.iter().filter(|outcome| outcome.error.is_some()) {
+                    eprintln!(
+                        "Runner {} failed: {}",
+                        outcome.runner_no,
+                        outcome.error.as_deref().unwrap_or_default()
+                    );
+                }
+                outcomes
+                    .into_iter()
+                    .filter_map(|outcome| outcome.report)
+                    .collect()
+            };
+            if json {
+                println!("{}", serde_json::to_string_pretty(&reports)?);
+            } else {
+                println!(
+                    "{:>5} {:>6} {:<28} {:<12} {:>12} {:>10} {:>8} {:>9}",
+                    "id", "runner", "device", "target", "mean MH/s", "stddev", "warmup", "change"
+                );
+                for compared in &reports {
+                    let report = &compared.report;
+                    println!(
+                        "{:>5} {:>6} {:<28} {:<12} {:>12.2} {:>10.2} {:>8} {:>9}",
+                        report.id,
+                        report.runner_no,
+                        report.device_name.as_deref().unwrap_or("-").trim(),
+                        report.target_kind,
+                        report.mean_speed,
+                        report.stddev_speed,
+                        report
+                            .warmup_secs
+                            .map(|warmup| format!("{warmup:.1}s"))
+                            .unwrap_or("-".to_string()),
+                        compared
+                            .comparison
+                            .as_ref()
+                            .map(|c| format!("{:+.1}%", c.change_percent))
+                            .unwrap_or("-".to_string())
+                    );
+                }
+            }
+            let regressions = reports
+                .iter()
+                .filter_map(|compared| compared.comparison.as_ref().map(|c| (compared, c)))
+                .filter(|(_, comparison)| comparison.regression)
+                .collect::<Vec<_>>();
+            for (compared, comparison) in &regressions {
+                println!(
+                    "Regression: report {} is {:.1}% slower than report {}",
+                    compared.report.id, -comparison.change_percent, comparison.previous_id
+                );
+            }
+            if !list && !regressions.is_empty() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Commands::Calibrate {
+            samples,
+            threads,
+            seed,
+            tolerance,
+            json,
+        } => {
+            let threads = threads.unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            });
+            log::info!(
+                "Scoring {} random addresses using {} threads",
+                samples,
+                threads
+            );
+            let report = calibrate_scores(samples, threads, seed);
+            let drifted = report.drifted(tolerance);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!(
+                    "{:<26} {:>10} {:>12} {:>10} {:>8} {:>8}",
+                    "category", "threshold", "expected", "observed", "ratio", "z"
+                );
+                for row in &report.rows {
+                    println!(
+                        "{:<26} {:>10} {:>12.1} {:>10} {:>8.3} {:>8.1}",
+                        row.category,
+                        row.threshold,
+                        row.expected_hits,
+                        row.observed_hits,
+                        row.ratio,
+                        row.z_score
+                    );
+                }
+                for row in &drifted {
+                    println!(
+                        "Drift: {} overstates difficulty {} by {:.2}x",
+                        row.category, row.threshold, row.ratio
+                    );
+                }
+            }
+            if !drifted.is_empty() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Commands::GenerateKeyBase {
+            keystore,
+            mnemonic,
+            words,
+            derivation_path,
+        } => {
+            let mnemonic = mnemonic.then_some((words, derivation_path));
+            match generate_key_base(&keystore, mnemonic) {
+                Ok(public_key_base) => {
+                    println!("{}", public_key_base);
+                }
+                Err(e) => {
+                    log::error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            Ok(())
+        }
+        Commands::CombineKey {
+            key_base_keystore,
+            private_key_base_file,
+            private_key_add,
+            expected_address,
+            keystore,
+        } => {
+            let private_key_base = match &private_key_base_file {
+                Some(path) => read_secret_input(path),
+                None => read_key_base_keystore(&key_base_keystore),
+            };
+            let result = private_key_base.and_then(|private_key_base| {
+                combine_key(
+                    &private_key_base,
+                    &private_key_add,
+                    expected_address.as_deref(),
+                    keystore.as_deref(),
+                )
+            });
+            match result {
+                Ok(output) => {
+                    println!("{}", output);
+                }
+                Err(e) => {
+                    log::error!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            Ok(())
+        }
+
         Commands::Test {} => {
             test_run_provider().await;
             //test_command(conn).await;
@@ -0,0 +1,298 @@
+use crate::config::get_result_queue_hard_limit;
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use crate::fancy::FancyDbObj;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Append-only jsonl segment for results that did not fit in memory.
+/// Read position is kept in a sidecar file, so consumed results are not replayed after restart
+#[derive(Debug)]
+struct SpillSegment {
+    path: PathBuf,
+    offset_path: PathBuf,
+    writer: File,
+    read_offset: u64,
+    pending: usize,
+}
+
+impl SpillSegment {
+    fn open(path: &Path) -> Result<Self, AddressologyError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                err_custom_create!("Failed to create spill dir {}: {e}", parent.display())
+            })?;
+        }
+        let offset_path = path.with_extension("offset");
+        let read_offset = std::fs::read_to_string(&offset_path)
+            .ok()
+            .and_then(|offset| offset.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+        let writer = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| err_custom_create!("Failed to open spill file {}: {e}", path.display()))?;
+        let mut segment = Self {
+            path: path.to_path_buf(),
+            offset_path,
+            writer,
+            read_offset,
+            pending: 0,
+        };
+        segment.recover()?;
+        Ok(segment)
+    }
+
+    /// Count complete lines after read offset and cut off partial line left by a crash
+    fn recover(&mut self) -> Result<(), AddressologyError> {
+        let len = self.file_len()?;
+        if self.read_offset > len {
+            log::warn!(
+                "Spill offset {} is past end of {}, starting over",
+                self.read_offset,
+                self.path.display()
+            );
+            self.read_offset = 0;
+        }
+        let mut reader = self.reader_at(self.read_offset)?;
+        let mut complete_end = self.read_offset;
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_until(b'\n', &mut line)
+                .map_err(|e| err_custom_create!("Failed to read spill file: {e}"))?;
+            if read == 0 || line.last() != Some(&b'\n') {
+                break;
+            }
+            complete_end += read as u64;
+            self.pending += 1;
+        }
+        if complete_end < len {
+            log::warn!(
+                "Dropping {} bytes of partial record from {}",
+                len - complete_end,
+                self.path.display()
+            );
+            self.writer
+                .set_len(complete_end)
+                .map_err(|e| err_custom_create!("Failed to truncate spill file: {e}"))?;
+        }
+        Ok(())
+    }
+
+    fn file_len(&self) -> Result<u64, AddressologyError> {
+        self.writer
+            .metadata()
+            .map(|metadata| metadata.len())
+            .map_err(|e| err_custom_create!("Failed to read spill file metadata: {e}"))
+    }
+
+    fn reader_at(&self, offset: u64) -> Result<BufReader<File>, AddressologyError> {
+        let mut file = File::open(&self.path)
+            .map_err(|e| err_custom_create!("Failed to open spill file: {e}"))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| err_custom_create!("Failed to seek spill file: {e}"))?;
+        Ok(BufReader::new(file))
+    }
+
+    fn append(&mut self, fancy: &FancyDbObj) -> Result<(), AddressologyError> {
+        let mut line = serde_json::to_vec(fancy)
+            .map_err(|e| err_custom_create!("Failed to serialize result: {e}"))?;
+        line.push(b'\n');
+        self.writer
+            .write_all(&line)
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| err_custom_create!("Failed to write spill file: {e}"))?;
+        self.pending += 1;
+        Ok(())
+    }
+
+    /// Read up to limit oldest spilled results, segment is emptied once everything is read
+    fn read(&mut self, limit: usize) -> Result<Vec<FancyDbObj>, AddressologyError> {
+        let mut reader = self.reader_at(self.read_offset)?;
+        let mut results = Vec::new();
+        let mut line = String::new();
+        while results.len() < limit && self.pending > 0 {
+            line.clear();
+            let read = reader
+                .read_line(&mut line)
+                .map_err(|e| err_custom_create!("Failed to read spill file: {e}"))?;
+            if read == 0 {
+                break;
+            }
+            self.read_offset += read as u64;
+            self.pending -= 1;
+            match serde_json::from_str::<FancyDbObj>(line.trim_end()) {
+                Ok(fancy) => results.push(fancy),
+                Err(err) => log::error!("Skipping corrupted spilled result: {err}"),
+            }
+        }
+        if self.pending == 0 {
+            self.writer
+                .set_len(0)
+                .map_err(|e| err_custom_create!("Failed to truncate spill file: {e}"))?;
+            self.read_offset = 0;
+        }
+        std::fs::write(&self.offset_path, self.read_offset.to_string())
+            .map_err(|e| err_custom_create!("Failed to write spill offset: {e}"))?;
+        Ok(results)
+    }
+}
+
+/// FIFO of found results, keeps at most memory_cap in memory and spills the rest to disk
+#[derive(Debug)]
+pub struct ResultQueue {
+    memory: VecDeque<FancyDbObj>,
+    memory_cap: usize,
+    /// Backlog size at which cruncher is paused
+    hard_limit: usize,
+    spill: Option<SpillSegment>,
+}
+
+impl Default for ResultQueue {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl ResultQueue {
+    /// Queue without spill file, memory is not limited
+    pub fn in_memory() -> Self {
+        Self {
+            memory: VecDeque::new(),
+            memory_cap: usize::MAX,
+            hard_limit: get_result_queue_hard_limit(),
+            spill: None,
+        }
+    }
+
+    pub fn with_hard_limit(mut self, hard_limit: usize) -> Self {
+        self.hard_limit = hard_limit;
+        self
+    }
+
+    /// Results left in spill file from previous run are loaded back
+    pub fn with_spill(spill_path: &Path, memory_cap: usize) -> Result<Self, AddressologyError> {
+        let spill = SpillSegment::open(spill_path)?;
+        if spill.pending > 0 {
+            log::info!(
+                "Reloaded {} spilled results from {}",
+                spill.pending,
+                spill_path.display()
+            );
+        }
+        Ok(Self {
+            memory: VecDeque::new(),
+            memory_cap: memory_cap.max(1),
+            hard_limit: get_result_queue_hard_limit(),
+            spill: Some(spill),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.memory.len() + self.spilled_len()
+    }
+
+    pub fn memory_len(&self) -> usize {
+        self.memory.len()
+    }
+
+    pub fn spilled_len(&self) -> usize {
+        self.spill.as_ref().map(|spill| spill.pending).unwrap_or(0)
+    }
+
+    pub fn is_over_hard_limit(&self) -> bool {
+        self.len() > self.hard_limit
+    }
+
+    /// Paused cruncher can start again once backlog drops to half of the limit
+    pub fn is_drained(&self) -> bool {
+        self.len() <= self.hard_limit / 2
+    }
+
+    /// Spilled results are newer than those in memory, so once spilling started
+    /// everything goes to disk until it is read back
+    pub fn push(&mut self, fancy: FancyDbObj) {
+        if let Some(spill) = self.spill.as_mut() {
+            if self.memory.len() >= self.memory_cap || spill.pending > 0 {
+                match spill.append(&fancy) {
+                    Ok(()) => return,
+                    Err(err) => log::error!("Keeping {} in memory: {err}", fancy.address),
+                }
+            }
+        }
+        self.memory.push_back(fancy);
+    }
+
+    /// Spilled results are read straight from disk, so only results handed out are marked read
+    pub fn drain(&mut self, limit: usize) -> Vec<FancyDbObj> {
+        let available = self.memory.len().min(limit);
+        let mut results = self.memory.drain(..available).collect::<Vec<_>>();
+        if let Some(spill) = self.spill.as_mut() {
+            if results.len() < limit && spill.pending > 0 {
+                match spill.read(limit - results.len()) {
+                    Ok(spilled) => results.extend(spilled),
+                    Err(err) => log::error!("Failed to read spilled results: {err}"),
+                }
+            }
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::ops::test_fancy_obj;
+
+    fn results(count: u64) -> Vec<FancyDbObj> {
+        (0..count).map(test_fancy_obj).collect()
+    }
+
+    #[test]
+    fn test_result_queue_spill() {
+        let dir = std::env::temp_dir().join(format!("result-queue-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("runner-0.jsonl");
+        let all = results(25);
+
+        let mut queue = ResultQueue::with_spill(&path, 10).unwrap();
+        for fancy in all.iter().take(20) {
+            queue.push(fancy.clone());
+        }
+        assert_eq!(queue.memory_len(), 10);
+        assert_eq!(queue.spilled_len(), 10);
+        let first = queue.drain(4);
+        assert_eq!(first, all[0..4]);
+        // memory has room again, but order is kept by spilling until spill is read
+        queue.push(all[20].clone());
+        assert_eq!(queue.spilled_len(), 11);
+        let second = queue.drain(10);
+        assert_eq!(second, all[4..14]);
+        assert_eq!(queue.len(), 7);
+        drop(queue);
+
+        // restart reloads only what was not consumed, partial line from crash is dropped
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"address\":\"0x00").unwrap();
+        drop(file);
+        let mut queue = ResultQueue::with_spill(&path, 10).unwrap();
+        assert_eq!(queue.len(), 7);
+        queue.push(all[21].clone());
+        let rest = queue.drain(100);
+        assert_eq!(rest, all[14..22]);
+        assert_eq!(queue.len(), 0);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+
+        let mut memory = ResultQueue::in_memory();
+        for fancy in &all {
+            memory.push(fancy.clone());
+        }
+        assert_eq!(memory.drain(100), all);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,16 +1,24 @@
 use crate::err_custom_create;
 use crate::error::AddressologyError;
-use crate::fancy::FancyDbObj;
+use crate::fancy::{
+    parse_fancy_create2, parse_fancy_create3, parse_fancy_hook, score_fast, FancyDbObj,
+    FancyScoreCategory, FastScore, HookFlagsTarget, SCORED_CATEGORIES,
+};
+use crate::hash::Create3Scheme;
+use crate::result_queue::ResultQueue;
 use crate::types::DbAddress;
 use parking_lot::Mutex;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::Sender;
 use tokio::time::sleep;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +29,14 @@ pub struct CrunchRunnerData {
     total_computed: Option<f64>,
     reported_speed: Option<f64>,
     found_addresses_count: u64,
+    /// Results dropped by ingestion filter
+    rejected_addresses_count: u64,
+    rejected_sampled_count: u64,
+    /// Results not sent to database because result store was full, they are kept in queue
+    sink_dropped_count: u64,
+    /// Process was stopped because results are neither consumed nor stored,
+    /// it is resumed when queue is drained or result store has room again
+    paused_for_backlog: bool,
     last_updated_speed: Option<chrono::DateTime<chrono::Utc>>,
     last_address_found: Option<chrono::DateTime<chrono::Utc>>,
 }
@@ -33,6 +49,10 @@ impl CrunchRunnerData {
             total_computed: None,
             reported_speed: None,
             found_addresses_count: 0,
+            rejected_addresses_count: 0,
+            rejected_sampled_count: 0,
+            sink_dropped_count: 0,
+            paused_for_backlog: false,
             last_updated_speed: None,
             last_address_found: None,
         }
@@ -44,9 +64,177 @@ impl CrunchRunnerData {
 pub enum WorkTarget {
     Factory(DbAddress),
     PublicKeyBase(String),
+    /// Cruncher has no option for init code hash, so this target is refused by validate.
+    /// Create2 results are verified here when they come from other sources
+    #[serde(rename_all = "camelCase")]
+    Create2 {
+        deployer: DbAddress,
+        init_code_hash: String,
+    },
+    /// CREATE3 factory with explicit proxy scheme, Factory target always uses plain salt.
+    /// Cruncher gets only the factory, so schemes deriving salt are refused by validate
+    #[serde(rename_all = "camelCase")]
+    Create3 {
+        factory: DbAddress,
+        scheme: Create3Scheme,
+        caller: Option<DbAddress>,
+        /// Needed for CreateX salts with cross-chain redeploy protection
+        #[serde(default)]
+        chain_id: Option<u64>,
+    },
+    /// Cruncher has no option for hook flags, so this target is refused by validate.
+    /// Results with flags are verified here when they come from other sources
+    #[serde(rename_all = "camelCase")]
+    UniswapHook {
+        factory: DbAddress,
+        flags: HookFlagsTarget,
+    },
     Default,
 }
 
+impl WorkTarget {
+    /// Name of target type, benchmarks are compared only within the same kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WorkTarget::Factory(_) => "factory",
+            WorkTarget::PublicKeyBase(_) => "publicKeyBase",
+            WorkTarget::Create2 { .. } => "create2",
+            WorkTarget::Create3 { .. } => "create3",
+            WorkTarget::UniswapHook { .. } => "uniswapHook",
+            WorkTarget::Default => "default",
+        }
+    }
+
+    /// Refuse targets whose results cruncher cannot produce with its options
+    pub fn validate(&self) -> Result<(), AddressologyError> {
+        match self {
+            WorkTarget::UniswapHook { flags, .. } => Err(err_custom_create!(
+                "Cruncher cannot mine hook flags, only 1 in {:.0} vanity results would match them",
+                flags.difficulty()
+            )),
+            // cruncher is started only with factory (-f) or public key base (-z),
+            // it has no documented option for init code hash
+            WorkTarget::Create2 { .. } => Err(err_custom_create!(
+                "Cruncher cannot mine create2 addresses with custom init code hash"
+            )),
+            // derived salt depends on the salt itself, so it cannot be given to cruncher upfront
+            WorkTarget::Create3 { scheme, .. } if !scheme.is_plain_salt() => Err(
+                err_custom_create!("Cruncher cannot mine create3 addresses of {scheme} scheme"),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Cruncher is stopped as soon as it finds address with score in category at least min_score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopCondition {
+    pub category: FancyScoreCategory,
+    pub min_score: f64,
+}
+
+impl StopCondition {
+    /// Score is the same fast score ingestion filter uses, result is scored only once
+    pub fn is_met(&self, score: &FastScore) -> bool {
+        SCORED_CATEGORIES
+            .iter()
+            .position(|category| *category == self.category)
+            .is_some_and(|idx| score.entries[idx].score >= self.min_score)
+    }
+}
+
+/// Sampled rejected results are kept up to this count, older are dropped
+const MAX_REJECTED_SAMPLES: usize = 10000;
+
+/// Result is kept if it reaches min total score or min difficulty in any of listed categories.
+/// Filter without thresholds keeps everything
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestionFilter {
+    #[serde(default)]
+    pub min_total_score: Option<f64>,
+    /// Category key (like leading_zeroes) to minimum FancyScoreEntry difficulty
+    #[serde(default)]
+    pub min_difficulty: BTreeMap<String, f64>,
+    /// Percent of rejected results kept aside for calibration
+    #[serde(default)]
+    pub sample_rejected_percent: f64,
+}
+
+impl IngestionFilter {
+    pub fn validate(&self) -> Result<(), AddressologyError> {
+        if !(0.0..=100.0).contains(&self.sample_rejected_percent) {
+            return Err(err_custom_create!(
+                "Sample percent has to be between 0 and 100, got {}",
+                self.sample_rejected_percent
+            ));
+        }
+        for category in self.min_difficulty.keys() {
+            if !SCORED_CATEGORIES.iter().any(|c| &c.to_string() == category) {
+                return Err(err_custom_create!("Unknown category {category}"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn accepts(&self, score: &FastScore) -> bool {
+        if self.min_total_score.is_none() && self.min_difficulty.is_empty() {
+            return true;
+        }
+        if self
+            .min_total_score
+            .is_some_and(|min_score| score.total_score() >= min_score)
+        {
+            return true;
+        }
+        SCORED_CATEGORIES
+            .iter()
+            .zip(score.entries.iter())
+            .any(|(category, entry)| {
+                self.min_difficulty
+                    .get(&category.to_string())
+                    .is_some_and(|min_difficulty| entry.difficulty >= *min_difficulty)
+            })
+    }
+}
+
+/// Filter captured when runner starts, with place for sampled rejected results
+#[derive(Debug)]
+struct IngestionGate {
+    filter: IngestionFilter,
+    rejected_samples: Arc<Mutex<VecDeque<FancyDbObj>>>,
+}
+
+impl IngestionGate {
+    /// Returns false when result is rejected, rejected results only update counters
+    fn admit(
+        &self,
+        fdb: &FancyDbObj,
+        score: &FastScore,
+        context: &Mutex<CrunchRunnerData>,
+    ) -> bool {
+        if self.filter.accepts(score) {
+            return true;
+        }
+        let sampled = self.filter.sample_rejected_percent > 0.0
+            && rand::rng().random::<f64>() * 100.0 < self.filter.sample_rejected_percent;
+        if sampled {
+            let mut samples = self.rejected_samples.lock();
+            if samples.len() >= MAX_REJECTED_SAMPLES {
+                samples.pop_front();
+            }
+            samples.push_back(fdb.clone());
+        }
+        let mut context = context.lock();
+        context.rejected_addresses_count += 1;
+        if sampled {
+            context.rejected_sampled_count += 1;
+        }
+        false
+    }
+}
+
 #[derive(Debug)]
 pub struct CrunchRunner {
     exe_path: PathBuf,
@@ -60,10 +248,18 @@ pub struct CrunchRunner {
     stderr_thread: Option<thread::JoinHandle<()>>,
 
     shared_data: Arc<Mutex<CrunchRunnerData>>,
-    addresses_deque: Arc<Mutex<VecDeque<FancyDbObj>>>,
+    addresses_deque: Arc<Mutex<ResultQueue>>,
+    /// Found addresses are also sent here to be stored
+    result_sink: Option<Sender<FancyDbObj>>,
 
     current_target: WorkTarget,
     work_target: WorkTarget,
+    stop_condition: Option<StopCondition>,
+    /// Used for every target unless target filter is set
+    ingestion_filter: Option<IngestionFilter>,
+    /// Belongs to work target, cleared when target changes
+    target_ingestion_filter: Option<IngestionFilter>,
+    rejected_samples: Arc<Mutex<VecDeque<FancyDbObj>>>,
 }
 
 impl Drop for CrunchRunner {
@@ -77,11 +273,17 @@ impl Drop for CrunchRunner {
     }
 }
 
+/// Returns true if process should be stopped, because found address met the stop condition
+/// or unconsumed results are over the hard limit
 fn parse_line(
     str: String,
     context: Arc<Mutex<CrunchRunnerData>>,
-    address_deque: Arc<Mutex<VecDeque<FancyDbObj>>>,
-) -> Result<(), AddressologyError> {
+    address_deque: Arc<Mutex<ResultQueue>>,
+    result_sink: Option<&Sender<FancyDbObj>>,
+    target: &WorkTarget,
+    stop_condition: Option<&StopCondition>,
+    ingestion: Option<&IngestionGate>,
+) -> Result<bool, AddressologyError> {
     log::trace!("Output: {}", str);
     let device_no = context.lock().runner_no;
     //log::info!("Output: {}", str);
@@ -110,7 +312,11 @@ fn parse_line(
                 .map_err(|err| err_custom_create!("Failed to parse address {err}"))?,
             salt: split[0].to_string(),
             factory,
+            init_code_hash: None,
             public_key_base,
+            create3_scheme: None,
+            caller: None,
+            chain_id: None,
             created: Default::default(),
             score: 0.0,
             owner: None,
@@ -119,12 +325,123 @@ fn parse_line(
             job: None,
         };
 
-        address_deque.lock().push_back(fdb);
+        let fdb = match target {
+            // cruncher searches only for vanity, so hook flags have to be checked here
+            WorkTarget::UniswapHook { factory, flags } => {
+                match parse_fancy_hook(fdb.salt.clone(), factory.addr(), flags) {
+                    Ok(verified) if verified.address == fdb.address => verified,
+                    Ok(verified) => {
+                        return Err(err_custom_create!(
+                            "Reported address {} does not match computed {}",
+                            fdb.address,
+                            verified.address
+                        ));
+                    }
+                    Err(err) => {
+                        log::debug!("Rejected hook candidate {}: {err}", fdb.address);
+                        return Ok(false);
+                    }
+                }
+            }
+            WorkTarget::Create2 {
+                deployer,
+                init_code_hash,
+            } => {
+                let verified =
+                    parse_fancy_create2(fdb.salt.clone(), deployer.addr(), init_code_hash.clone())?;
+                if verified.address != fdb.address {
+                    return Err(err_custom_create!(
+                        "Reported address {} does not match computed create2 address {}",
+                        fdb.address,
+                        verified.address
+                    ));
+                }
+                verified
+            }
+            WorkTarget::Create3 {
+                factory,
+                scheme,
+                caller,
+                chain_id,
+            } => {
+                let verified = parse_fancy_create3(
+                    fdb.salt.clone(),
+                    factory.addr(),
+                    *scheme,
+                    caller.map(|caller| caller.addr()),
+                    *chain_id,
+                )?;
+                if verified.address != fdb.address {
+                    return Err(err_custom_create!(
+                        "Reported address {} does not match computed {} create3 address {}",
+                        fdb.address,
+                        scheme,
+                        verified.address
+                    ));
+                }
+                verified
+            }
+            _ => fdb,
+        };
+
+        let score = (ingestion.is_some() || stop_condition.is_some())
+            .then(|| score_fast(&fdb.address.addr()));
+        if let (Some(gate), Some(score)) = (ingestion, &score) {
+            if !gate.admit(&fdb, score, &context) {
+                log::trace!("Address {} rejected by ingestion filter", fdb.address);
+                return Ok(false);
+            }
+        }
+
+        let stop_condition_met = match (stop_condition, &score) {
+            (Some(cond), Some(score)) => cond.is_met(score),
+            _ => false,
+        };
+        if stop_condition_met {
+            log::info!(
+                "Address {} met stop condition {:?}",
+                fdb.address,
+                stop_condition
+            );
+        }
+        let stored = match result_sink {
+            Some(result_sink) => match result_sink.try_send(fdb.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    let mut context = context.lock();
+                    context.sink_dropped_count += 1;
+                    log::debug!(
+                        "Result store is full, {} is kept only in queue ({} dropped)",
+                        fdb.address,
+                        context.sink_dropped_count
+                    );
+                    false
+                }
+                Err(TrySendError::Closed(_)) => {
+                    log::warn!(
+                        "Result store is closed, {} is kept only in queue",
+                        fdb.address
+                    );
+                    false
+                }
+            },
+            None => false,
+        };
+        let (queue_len, over_limit) = {
+            let mut queue = address_deque.lock();
+            queue.push(fdb);
+            (queue.len(), queue.is_over_hard_limit())
+        };
         let mut update_context = context.lock();
         update_context.found_addresses_count += 1;
         //log::info!("Address found: {}", update_context.found_addresses_count);
         update_context.last_address_found = Some(chrono::Utc::now());
-        Ok(())
+        // results in database are safe, so backlog only matters when store does not keep up
+        if over_limit && !stored && !update_context.paused_for_backlog {
+            log::warn!("{queue_len} results are neither consumed nor stored, pausing runner");
+            update_context.paused_for_backlog = true;
+        }
+        Ok(stop_condition_met || update_context.paused_for_backlog)
     } else {
         // Extract the relevant part after "Total compute"
         if let Some(data) = str.split("Total compute ").nth(1) {
@@ -141,7 +458,7 @@ fn parse_line(
                 c.reported_speed = Some(rate);
                 c.total_computed = Some(total_compute);
                 c.last_updated_speed = Some(chrono::Utc::now());
-                Ok(())
+                Ok(false)
             } else {
                 log::warn!("Failed to parse line: {}", str);
                 Err(err_custom_create!("Failed to parse line"))
@@ -149,10 +466,10 @@ fn parse_line(
         } else if let Some(data) = str.split(&format!("Device {device_no}")).nth(1) {
             let mut c = context.lock();
             c.device_name = Some(data.to_string());
-            Ok(())
+            Ok(false)
         } else {
             // log::warn!("Unknown line {}", str);
-            Ok(())
+            Ok(false)
         }
     }
 }
@@ -168,15 +485,23 @@ impl CrunchRunner {
             stderr_thread: None,
             shared_data: Arc::new(Mutex::new(CrunchRunnerData::new(runner_no))),
             addresses_deque: Arc::new(Default::default()),
+            result_sink: None,
             current_target: WorkTarget::Default,
             work_target: WorkTarget::Default,
+            stop_condition: None,
+            ingestion_filter: None,
+            target_ingestion_filter: None,
+            rejected_samples: Arc::new(Default::default()),
             is_enabled: true,
         }
     }
     pub fn consume_results(&self, limit: usize) -> Vec<FancyDbObj> {
-        let mut deque = self.addresses_deque.lock();
-        let available = deque.len().min(limit); // Ensure we don't over-drain
-        deque.drain(..available).collect()
+        self.addresses_deque.lock().drain(limit)
+    }
+    pub fn consume_rejected_samples(&self, limit: usize) -> Vec<FancyDbObj> {
+        let mut samples = self.rejected_samples.lock();
+        let available = samples.len().min(limit);
+        samples.drain(..available).collect()
     }
     pub fn is_enabled(&self) -> bool {
         self.is_enabled
@@ -190,6 +515,12 @@ impl CrunchRunner {
     pub fn reported_speed(&self) -> Option<f64> {
         self.shared_data.lock().reported_speed
     }
+    pub fn last_updated_speed(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.shared_data.lock().last_updated_speed
+    }
+    pub fn device_name(&self) -> Option<String> {
+        self.shared_data.lock().device_name.clone()
+    }
     pub fn total_computed(&self) -> Option<f64> {
         self.shared_data.lock().total_computed
     }
@@ -199,6 +530,42 @@ impl CrunchRunner {
     pub fn queue_len(&self) -> usize {
         self.addresses_deque.lock().len()
     }
+    pub fn queue_spilled_len(&self) -> usize {
+        self.addresses_deque.lock().spilled_len()
+    }
+
+    /// Replaces empty in-memory queue, used at startup to reload spilled results
+    pub fn set_result_queue(&mut self, queue: ResultQueue) {
+        *self.addresses_deque.lock() = queue;
+    }
+
+    /// Paused runner may start again once results are consumed below half of the limit
+    /// or result store has room for new results
+    pub fn can_resume(&self) -> bool {
+        self.shared_data.lock().paused_for_backlog
+            && (self.addresses_deque.lock().is_drained()
+                || self
+                    .result_sink
+                    .as_ref()
+                    .is_some_and(|result_sink| result_sink.capacity() > 0))
+    }
+
+    /// Start runner paused for backlog again, called after consume and periodically
+    pub async fn resume_if_drained(&mut self) -> Result<bool, AddressologyError> {
+        if !self.can_resume() {
+            return Ok(false);
+        }
+        if !self.is_started() {
+            log::info!("Result backlog drained, resuming runner");
+            self.start(None).await?;
+        }
+        self.shared_data.lock().paused_for_backlog = false;
+        Ok(true)
+    }
+
+    pub fn set_result_sink(&mut self, result_sink: Sender<FancyDbObj>) {
+        self.result_sink = Some(result_sink);
+    }
 
     pub fn set_contract(&mut self, contract: DbAddress) {
         self.contract = Some(contract);
@@ -210,6 +577,37 @@ impl CrunchRunner {
 
     pub fn set_target(&mut self, target: WorkTarget) {
         self.work_target = target;
+        self.target_ingestion_filter = None;
+    }
+
+    pub fn set_stop_condition(&mut self, stop_condition: Option<StopCondition>) {
+        self.stop_condition = stop_condition;
+    }
+
+    pub fn stop_condition(&self) -> Option<StopCondition> {
+        self.stop_condition.clone()
+    }
+
+    pub fn set_ingestion_filter(&mut self, ingestion_filter: Option<IngestionFilter>) {
+        self.ingestion_filter = ingestion_filter;
+    }
+
+    pub fn set_target_ingestion_filter(&mut self, ingestion_filter: Option<IngestionFilter>) {
+        self.target_ingestion_filter = ingestion_filter;
+    }
+
+    /// Filter applied to results of work target
+    pub fn ingestion_filter(&self) -> Option<IngestionFilter> {
+        self.target_ingestion_filter
+            .clone()
+            .or_else(|| self.ingestion_filter.clone())
+    }
+
+    fn ingestion_gate(&self) -> Option<IngestionGate> {
+        self.ingestion_filter().map(|filter| IngestionGate {
+            filter,
+            rejected_samples: self.rejected_samples.clone(),
+        })
     }
 
     pub async fn restart(&mut self) -> Result<(), AddressologyError> {
@@ -273,6 +671,21 @@ impl CrunchRunner {
                         rounds.to_string(),
                     ]
                 }
+                target @ (WorkTarget::Create2 { .. } | WorkTarget::UniswapHook { .. }) => {
+                    return Err(err_custom_create!(
+                        "Cruncher cannot mine {} target",
+                        target.kind()
+                    ));
+                }
+                WorkTarget::Create3 { factory, .. } => {
+                    let rounds = 1000;
+                    vec![
+                        "-f".to_string(),
+                        factory.to_string(),
+                        "-r".to_string(),
+                        rounds.to_string(),
+                    ]
+                }
                 WorkTarget::PublicKeyBase(public_key_base) => {
                     let rounds = 100;
                     vec![
@@ -338,18 +751,35 @@ impl CrunchRunner {
         let stdout_shared_data = self.shared_data.clone();
         let stdout_deque = self.addresses_deque.clone();
         let stdout_pid = child.id();
+        let stdout_target = self.current_target.clone();
+        let stdout_result_sink = self.result_sink.clone();
+        let stdout_stop_condition = self.stop_condition.clone();
+        let stdout_ingestion = self.ingestion_gate();
         let child_pr = self.child_process.clone();
         let stdout_thread = thread::spawn(move || {
             let reader = BufReader::new(stdout);
             for line in reader.lines() {
                 match line {
-                    Ok(line) => {
-                        if let Err(err) =
-                            parse_line(line, stdout_shared_data.clone(), stdout_deque.clone())
-                        {
+                    Ok(line) => match parse_line(
+                        line,
+                        stdout_shared_data.clone(),
+                        stdout_deque.clone(),
+                        stdout_result_sink.as_ref(),
+                        &stdout_target,
+                        stdout_stop_condition.as_ref(),
+                        stdout_ingestion.as_ref(),
+                    ) {
+                        Ok(true) => {
+                            if let Some(child) = child_pr.lock().as_mut() {
+                                log::info!("Stop condition met or result backlog full - killing process {stdout_pid}");
+                                let _ = child.kill();
+                            }
+                        }
+                        Ok(false) => {}
+                        Err(err) => {
                             log::error!("Error parsing line: {err}");
                         }
-                    }
+                    },
                     Err(err) => {
                         log::error!("Error reading line: {err}");
                     }
@@ -369,19 +799,35 @@ impl CrunchRunner {
         let stderr_shared_data = self.shared_data.clone();
         let stderr_address_deque = self.addresses_deque.clone();
         let stderr_pid = child.id();
+        let stderr_target = self.current_target.clone();
+        let stderr_result_sink = self.result_sink.clone();
+        let stderr_stop_condition = self.stop_condition.clone();
+        let stderr_ingestion = self.ingestion_gate();
+        let stderr_child_pr = self.child_process.clone();
         let stderr_thread = thread::spawn(move || {
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 match line {
-                    Ok(line) => {
-                        if let Err(err) = parse_line(
-                            line,
-                            stderr_shared_data.clone(),
-                            stderr_address_deque.clone(),
-                        ) {
+                    Ok(line) => match parse_line(
+                        line,
+                        stderr_shared_data.clone(),
+                        stderr_address_deque.clone(),
+                        stderr_result_sink.as_ref(),
+                        &stderr_target,
+                        stderr_stop_condition.as_ref(),
+                        stderr_ingestion.as_ref(),
+                    ) {
+                        Ok(true) => {
+                            if let Some(child) = stderr_child_pr.lock().as_mut() {
+                                log::info!("Stop condition met or result backlog full - killing process {stderr_pid}");
+                                let _ = child.kill();
+                            }
+                        }
+                        Ok(false) => {}
+                        Err(err) => {
                             log::error!("Error parsing line: {err}");
                         }
-                    }
+                    },
                     Err(err) => {
                         log::error!("Error reading line: {err}");
                     }
@@ -438,3 +884,188 @@ pub async fn test_run() {
         }
     }
 }
+
+/// Resumes runners paused for backlog without waiting for someone to consume results,
+/// results of runners with result store get to database once the store catches up
+pub async fn resume_drained_loop(
+    runners: Vec<Arc<tokio::sync::Mutex<CrunchRunner>>>,
+    interval_secs: u64,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+        for (runner_no, runner) in runners.iter().enumerate() {
+            if let Err(err) = runner.lock().await.resume_if_drained().await {
+                log::error!("Failed to resume runner {runner_no}: {err}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ingestion_filter() {
+        let context = Arc::new(Mutex::new(CrunchRunnerData::new(0)));
+        let deque = Arc::new(Mutex::new(ResultQueue::in_memory()));
+        let gate = IngestionGate {
+            filter: IngestionFilter {
+                min_total_score: None,
+                min_difficulty: BTreeMap::from([("leading_zeroes".to_string(), 1e6)]),
+                sample_rejected_percent: 100.0,
+            },
+            rejected_samples: Arc::new(Default::default()),
+        };
+        assert!(gate.filter.validate().is_ok());
+        let factory = "0x9e3f8eae49e442a323ef2094f277bf62752e6995";
+        for address in [
+            "0x0000000000c0ffee000000000000000000000001",
+            "0x31585b5cd5557777376822555552bb555ee18882",
+        ] {
+            let line = format!(
+                "0x9a07547b2ac4220006e585000000000000000000000000000000000000000000,{address},{factory}"
+            );
+            parse_line(
+                line,
+                context.clone(),
+                deque.clone(),
+                None,
+                &WorkTarget::Default,
+                None,
+                Some(&gate),
+            )
+            .unwrap();
+        }
+        assert_eq!(deque.lock().len(), 1);
+        let data = context.lock().clone();
+        assert_eq!(data.found_addresses_count, 1);
+        assert_eq!(data.rejected_addresses_count, 1);
+        assert_eq!(data.rejected_sampled_count, 1);
+        assert_eq!(gate.rejected_samples.lock().len(), 1);
+
+        assert!(IngestionFilter::default().accepts(&score_fast(&web3::types::Address::zero())));
+        let invalid = IngestionFilter {
+            min_difficulty: BTreeMap::from([("nope".to_string(), 1.0)]),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_stop_condition() {
+        let address = DbAddress::from_str("0x0000000000c0ffee000000000000000000000001")
+            .unwrap()
+            .addr();
+        let score = score_fast(&address);
+        let reference = crate::fancy::score_fancy(address);
+        assert_eq!(reference.scores.get("leading_zeroes").unwrap().score, 10.0);
+        let condition = |min_score| StopCondition {
+            category: FancyScoreCategory::LeadingZeroes,
+            min_score,
+        };
+        assert!(condition(10.0).is_met(&score));
+        assert!(!condition(10.5).is_met(&score));
+    }
+
+    #[test]
+    fn test_work_target_validate() {
+        let factory = DbAddress::from_str("0x9e3f8eae49e442a323ef2094f277bf62752e6995").unwrap();
+        assert!(WorkTarget::Factory(factory).validate().is_ok());
+        let hook = WorkTarget::UniswapHook {
+            factory,
+            flags: HookFlagsTarget::exact(0x0080).unwrap(),
+        };
+        assert!(hook.validate().is_err());
+        let create2 = WorkTarget::Create2 {
+            deployer: factory,
+            init_code_hash: format!("0x{}", "11".repeat(32)),
+        };
+        assert!(create2.validate().is_err());
+        let create3 = |scheme| WorkTarget::Create3 {
+            factory,
+            scheme,
+            caller: Some(factory),
+            chain_id: Some(8453),
+        };
+        assert!(create3(Create3Scheme::Solady).validate().is_ok());
+        assert!(create3(Create3Scheme::Sequence).validate().is_ok());
+        assert!(create3(Create3Scheme::ZeframLou).validate().is_err());
+        assert!(create3(Create3Scheme::CreateX).validate().is_err());
+    }
+
+    #[test]
+    fn test_result_sink_full() {
+        let context = Arc::new(Mutex::new(CrunchRunnerData::new(0)));
+        let deque = Arc::new(Mutex::new(ResultQueue::in_memory()));
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        let factory = "0x9e3f8eae49e442a323ef2094f277bf62752e6995";
+        for address in [
+            "0x0000000000c0ffee000000000000000000000001",
+            "0x0000000000c0ffee000000000000000000000002",
+        ] {
+            let line = format!(
+                "0x9a07547b2ac4220006e585000000000000000000000000000000000000000000,{address},{factory}"
+            );
+            parse_line(
+                line,
+                context.clone(),
+                deque.clone(),
+                Some(&sender),
+                &WorkTarget::Default,
+                None,
+                None,
+            )
+            .unwrap();
+        }
+        // parsing never waits for the store, dropped result is still in queue
+        assert_eq!(deque.lock().len(), 2);
+        assert_eq!(context.lock().sink_dropped_count, 1);
+        assert!(receiver.try_recv().is_ok());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_backlog_pause_and_resume() {
+        let mut runner = CrunchRunner::new("missing_cruncher.exe".parse().unwrap(), 0);
+        runner.set_result_queue(ResultQueue::in_memory().with_hard_limit(4));
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        runner.set_result_sink(sender.clone());
+        let factory = "0x9e3f8eae49e442a323ef2094f277bf62752e6995";
+        let mut paused = Vec::new();
+        for i in 1..=6 {
+            let line = format!(
+                "0x9a07547b2ac4220006e585000000000000000000000000000000000000000000,0x0000000000c0ffee{i:024x},{factory}"
+            );
+            paused.push(
+                parse_line(
+                    line,
+                    runner.shared_data.clone(),
+                    runner.addresses_deque.clone(),
+                    Some(&sender),
+                    &WorkTarget::Default,
+                    None,
+                    None,
+                )
+                .unwrap(),
+            );
+        }
+        // first result went to store, the rest only to queue and the fifth is over the limit
+        assert_eq!(paused, [false, false, false, false, true, true]);
+        assert!(runner.shared_data().paused_for_backlog);
+        assert!(!runner.can_resume());
+        assert!(!runner.resume_if_drained().await.unwrap());
+
+        // store catching up is enough, nobody has to consume the queue
+        assert!(receiver.try_recv().is_ok());
+        assert!(runner.can_resume());
+        runner.result_sink = None;
+        assert!(!runner.can_resume());
+        assert_eq!(runner.consume_results(4).len(), 4);
+        assert!(runner.can_resume());
+        // resume tries to start the process again and stays paused when it cannot
+        assert!(runner.resume_if_drained().await.is_err());
+        assert!(runner.shared_data().paused_for_backlog);
+    }
+}
@@ -0,0 +1,364 @@
+use crate::err_custom_create;
+use crate::error::AddressologyError;
+use crate::runner::CrunchRunner;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// One day of minute buckets
+const MINUTE_BUCKETS: usize = 24 * 60;
+/// Thirty days of hour buckets
+const HOUR_BUCKETS: usize = 30 * 24;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySample {
+    pub time: DateTime<Utc>,
+    /// MH/s, None when runner is not started
+    pub speed: Option<f64>,
+    /// GH computed by current cruncher process, resets when process restarts
+    pub total_computed: Option<f64>,
+    pub found_count: u64,
+    pub queue_len: usize,
+}
+
+impl HistorySample {
+    pub fn from_runner(runner: &CrunchRunner, time: DateTime<Utc>) -> Self {
+        Self {
+            time,
+            speed: runner
+                .is_started()
+                .then(|| runner.reported_speed())
+                .flatten(),
+            total_computed: runner.total_computed(),
+            found_count: runner.found_addresses_count(),
+            queue_len: runner.queue_len(),
+        }
+    }
+}
+
+/// Aggregated samples of one minute or hour
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryBucket {
+    pub start: DateTime<Utc>,
+    pub samples: u64,
+    /// Samples taken while runner was started
+    pub speed_samples: u64,
+    pub speed_avg: Option<f64>,
+    pub speed_min: Option<f64>,
+    pub speed_max: Option<f64>,
+    /// GH computed within bucket
+    pub computed: f64,
+    /// Addresses found within bucket
+    pub found: u64,
+    pub queue_len_max: usize,
+}
+
+impl HistoryBucket {
+    fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            samples: 0,
+            speed_samples: 0,
+            speed_avg: None,
+            speed_min: None,
+            speed_max: None,
+            computed: 0.0,
+            found: 0,
+            queue_len_max: 0,
+        }
+    }
+
+    fn add(&mut self, sample: &HistorySample, computed: f64, found: u64) {
+        self.samples += 1;
+        if let Some(speed) = sample.speed {
+            self.speed_samples += 1;
+            let avg = self.speed_avg.unwrap_or(0.0);
+            self.speed_avg = Some(avg + (speed - avg) / self.speed_samples as f64);
+            self.speed_min = Some(self.speed_min.map_or(speed, |min| min.min(speed)));
+            self.speed_max = Some(self.speed_max.map_or(speed, |max| max.max(speed)));
+        }
+        self.computed += computed;
+        self.found += found;
+        self.queue_len_max = self.queue_len_max.max(sample.queue_len);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryResolution {
+    #[serde(rename = "raw")]
+    Raw,
+    #[serde(rename = "1m")]
+    Minute,
+    #[serde(rename = "1h")]
+    Hour,
+}
+
+impl FromStr for HistoryResolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "raw" => Ok(Self::Raw),
+            "1m" => Ok(Self::Minute),
+            "1h" => Ok(Self::Hour),
+            _ => Err(format!("Unknown resolution {s}, use raw, 1m or 1h")),
+        }
+    }
+}
+
+/// Bounded time series of one runner, raw samples plus minute and hour buckets
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerHistory {
+    raw_cap: usize,
+    raw: VecDeque<HistorySample>,
+    minute: VecDeque<HistoryBucket>,
+    hour: VecDeque<HistoryBucket>,
+    /// Deltas are not counted across restart of the server
+    #[serde(skip)]
+    last: Option<HistorySample>,
+}
+
+fn add_to_buckets(
+    buckets: &mut VecDeque<HistoryBucket>,
+    cap: usize,
+    bucket_secs: i64,
+    sample: &HistorySample,
+    computed: f64,
+    found: u64,
+) -> bool {
+    let timestamp = sample.time.timestamp();
+    let start = DateTime::from_timestamp(timestamp - timestamp.rem_euclid(bucket_secs), 0)
+        .unwrap_or(sample.time);
+    let opened = buckets.back().is_none_or(|bucket| bucket.start < start);
+    if opened {
+        buckets.push_back(HistoryBucket::new(start));
+        while buckets.len() > cap {
+            buckets.pop_front();
+        }
+    }
+    if let Some(bucket) = buckets.back_mut() {
+        bucket.add(sample, computed, found);
+    }
+    opened
+}
+
+impl RunnerHistory {
+    pub fn new(raw_cap: usize) -> Self {
+        Self {
+            raw_cap: raw_cap.max(1),
+            raw: VecDeque::new(),
+            minute: VecDeque::new(),
+            hour: VecDeque::new(),
+            last: None,
+        }
+    }
+
+    /// Returns true when sample opened new minute bucket
+    pub fn push(&mut self, sample: HistorySample) -> bool {
+        let (computed, found) = match &self.last {
+            Some(last) => {
+                let computed = match (last.total_computed, sample.total_computed) {
+                    (Some(last), Some(current)) if current >= last => current - last,
+                    // counter starts over with new cruncher process
+                    (_, Some(current)) => current,
+                    (_, None) => 0.0,
+                };
+                (
+                    computed,
+                    sample.found_count.saturating_sub(last.found_count),
+                )
+            }
+            None => (0.0, 0),
+        };
+        let opened = add_to_buckets(
+            &mut self.minute,
+            MINUTE_BUCKETS,
+            60,
+            &sample,
+            computed,
+            found,
+        );
+        add_to_buckets(&mut self.hour, HOUR_BUCKETS, 3600, &sample, computed, found);
+        self.raw.push_back(sample.clone());
+        while self.raw.len() > self.raw_cap {
+            self.raw.pop_front();
+        }
+        self.last = Some(sample);
+        opened
+    }
+
+    pub fn raw(&self, since: Option<DateTime<Utc>>) -> Vec<HistorySample> {
+        self.raw
+            .iter()
+            .filter(|sample| since.is_none_or(|since| sample.time >= since))
+            .cloned()
+            .collect()
+    }
+
+    pub fn buckets(
+        &self,
+        resolution: HistoryResolution,
+        since: Option<DateTime<Utc>>,
+    ) -> Vec<HistoryBucket> {
+        let buckets = match resolution {
+            HistoryResolution::Hour => &self.hour,
+            _ => &self.minute,
+        };
+        buckets
+            .iter()
+            .filter(|bucket| since.is_none_or(|since| bucket.start >= since))
+            .cloned()
+            .collect()
+    }
+
+    /// History saved by previous run, new history when file is missing or broken
+    pub fn load_or_new(path: &Path, raw_cap: usize) -> Self {
+        let loaded = std::fs::read(path).ok().and_then(|content| {
+            match serde_json::from_slice::<RunnerHistory>(&content) {
+                Ok(history) => Some(history),
+                Err(err) => {
+                    log::warn!("Ignoring broken runner history {}: {err}", path.display());
+                    None
+                }
+            }
+        });
+        match loaded {
+            Some(mut history) => {
+                history.raw_cap = raw_cap.max(1);
+                while history.raw.len() > history.raw_cap {
+                    history.raw.pop_front();
+                }
+                history
+            }
+            None => Self::new(raw_cap),
+        }
+    }
+
+    /// Written to temporary file first, so crash does not leave truncated history
+    pub fn save(&self, path: &Path) -> Result<(), AddressologyError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                err_custom_create!("Failed to create history dir {}: {e}", parent.display())
+            })?;
+        }
+        let content = serde_json::to_vec(self)
+            .map_err(|e| err_custom_create!("Failed to serialize runner history: {e}"))?;
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, content)
+            .and_then(|_| std::fs::rename(&tmp_path, path))
+            .map_err(|e| err_custom_create!("Failed to write {}: {e}", path.display()))
+    }
+}
+
+pub fn runner_history_path(dir: &Path, runner_no: usize) -> PathBuf {
+    dir.join(format!("runner-{runner_no}.json"))
+}
+
+/// Samples every runner each interval, history is saved whenever a minute bucket is opened
+pub async fn runner_history_loop(
+    runners: Vec<Arc<tokio::sync::Mutex<CrunchRunner>>>,
+    history: Arc<parking_lot::Mutex<Vec<RunnerHistory>>>,
+    interval_secs: u64,
+    dir: Option<PathBuf>,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+        for (runner_no, runner) in runners.iter().enumerate() {
+            let sample = HistorySample::from_runner(&*runner.lock().await, Utc::now());
+            let snapshot = {
+                let mut history = history.lock();
+                let Some(runner_history) = history.get_mut(runner_no) else {
+                    continue;
+                };
+                let opened = runner_history.push(sample);
+                (opened && dir.is_some()).then(|| runner_history.clone())
+            };
+            if let (Some(snapshot), Some(dir)) = (snapshot, dir.as_ref()) {
+                if let Err(err) = snapshot.save(&runner_history_path(dir, runner_no)) {
+                    log::error!("Failed to save history of runner {runner_no}: {err}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(
+        secs: i64,
+        speed: Option<f64>,
+        total_computed: f64,
+        found_count: u64,
+    ) -> HistorySample {
+        HistorySample {
+            time: DateTime::from_timestamp(1_700_000_000 - 1_700_000_000 % 3600 + secs, 0).unwrap(),
+            speed,
+            total_computed: Some(total_computed),
+            found_count,
+            queue_len: found_count as usize,
+        }
+    }
+
+    #[test]
+    fn test_runner_history() {
+        let mut history = RunnerHistory::new(3);
+        assert!(history.push(sample(0, Some(100.0), 1.0, 0)));
+        assert!(!history.push(sample(20, Some(80.0), 3.0, 2)));
+        assert!(!history.push(sample(40, None, 4.0, 3)));
+        // cruncher restarted, compute counter starts over
+        assert!(history.push(sample(60, Some(90.0), 0.5, 5)));
+        assert!(history.push(sample(3600, Some(60.0), 10.0, 6)));
+
+        let minutes = history.buckets(HistoryResolution::Minute, None);
+        assert_eq!(minutes.len(), 3);
+        assert_eq!(minutes[0].samples, 3);
+        assert_eq!(minutes[0].speed_samples, 2);
+        assert_eq!(minutes[0].speed_avg, Some(90.0));
+        assert_eq!(minutes[0].speed_min, Some(80.0));
+        assert_eq!(minutes[0].speed_max, Some(100.0));
+        assert_eq!(minutes[0].computed, 3.0);
+        assert_eq!(minutes[0].found, 3);
+        assert_eq!(minutes[0].queue_len_max, 3);
+        assert_eq!(minutes[1].computed, 0.5);
+        assert_eq!(minutes[1].found, 2);
+
+        let hours = history.buckets(HistoryResolution::Hour, None);
+        assert_eq!(hours.len(), 2);
+        assert_eq!(hours[0].samples, 4);
+        assert_eq!(hours[0].computed, 3.5);
+        assert_eq!(hours[1].computed, 9.5);
+        assert_eq!(
+            history
+                .buckets(HistoryResolution::Minute, Some(minutes[1].start))
+                .len(),
+            2
+        );
+        assert_eq!(history.raw(None).len(), 3);
+
+        let dir = std::env::temp_dir().join(format!("runner-history-{}", uuid::Uuid::new_v4()));
+        let path = runner_history_path(&dir, 0);
+        history.save(&path).unwrap();
+        let mut loaded = RunnerHistory::load_or_new(&path, 2);
+        assert_eq!(loaded.raw(None).len(), 2);
+        assert_eq!(loaded.buckets(HistoryResolution::Hour, None), hours);
+        // first sample after restart has no previous one to count deltas from
+        loaded.push(sample(3620, Some(60.0), 12.0, 7));
+        assert_eq!(
+            loaded.buckets(HistoryResolution::Hour, None)[1].computed,
+            9.5
+        );
+        assert_eq!("1h".parse(), Ok(HistoryResolution::Hour));
+        assert!("5m".parse::<HistoryResolution>().is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}